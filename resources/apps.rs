@@ -6,45 +6,3154 @@
 //! |--------|----------------------------------|--------------------------------|
 //! | GET    | /yeti-applications/apps          | List all apps                  |
 //! | GET    | /yeti-applications/apps/{id}     | Get single app detail          |
+//! | GET    | /yeti-applications/apps/templates | List app templates            |
+//! | GET    | /yeti-applications/apps/usage    | Rank apps by disk usage        |
+//! | GET    | /yeti-applications/apps/conflicts | Route/database collisions     |
+//! | GET    | /yeti-applications/apps/routes   | Tree of every served route     |
 //! | POST   | /yeti-applications/apps          | Create new app from template   |
-//! | PUT    | /yeti-applications/apps/{id}     | Update app config.yaml         |
-//! | DELETE | /yeti-applications/apps/{id}     | Remove app directory           |
+//! | PUT    | /yeti-applications/apps/{id}     | Patch app config.yaml          |
+//! | DELETE | /yeti-applications/apps/{id}     | Move app directory to trash    |
+//! | GET    | /yeti-applications/apps/{id}/export | Download app as tar.gz      |
+//! | POST   | /yeti-applications/apps/import   | Create app from tar.gz archive |
+//! | GET    | /yeti-applications/apps/trash    | List deleted apps              |
+//! | POST   | /yeti-applications/apps/trash/{entry}/restore | Restore deleted app |
+//! | DELETE | /yeti-applications/apps/trash/{entry} | Permanently remove deleted app |
+//! | POST   | /yeti-applications/apps/validate-config | Validate a config.yaml  |
+//! | POST   | /yeti-applications/apps/{id}/duplicate | Copy app under a new id  |
+//! | POST   | /yeti-applications/apps/bulk     | Enable/disable/delete/pull many apps |
+//! | POST   | /yeti-applications/apps/{id}/rename | Change an app's id          |
+//! | POST   | /yeti-applications/apps/{id}/build | Start static_files build job |
+//! | GET    | /yeti-applications/apps/{id}/build | List recent build jobs       |
+//! | GET    | /yeti-applications/apps/{id}/build/{job} | Build job state        |
+//! | POST   | /yeti-applications/apps/{id}/check | Compile-check app resources  |
+//! | POST   | /yeti-applications/apps/{id}/scaffold | Generate resource for a table |
+//! | GET    | /yeti-applications/apps/{id}/revisions | List config.yaml revisions |
+//! | GET    | /yeti-applications/apps/{id}/revisions/{rev} | Get one revision     |
+//! | GET    | /yeti-applications/apps/{id}/revisions/diff?from=&to= | Diff revisions |
+//! | POST   | /yeti-applications/apps/{id}/revisions/{rev}/rollback | Restore a revision |
+//!
+//! `PUT` bodies are RFC 7396 JSON Merge Patches by default (`null` removes a key),
+//! or RFC 6902 JSON Patches when sent as `application/json-patch+json`.
+//! `?dry_run=true` returns the resulting config and diff without writing.
+//! Every config.yaml write is checked against the typed model in `CONFIG_MODEL`
+//! and recorded in the `ConfigRevision` table (previous and new content, user, diff).
+//! Files are replaced atomically (temp file, fsync, rename) and the previous version
+//! is kept in the same backup area the files resource lists and restores.
+//!
+//! Deletes and renames follow the `admin.protection` policy: protected apps are refused, or
+//! require `?confirm=<app_id>`. Extension apps are protected by default.
+//!
+//! Builds run `static_files.build.command` in `static_files.build.sourceDir` on a
+//! background thread. Log lines and the final `end` event are written to the
+//! exported `BuildEvent` table, so `GET /admin/BuildEvent?stream=sse` follows a build.
+//!
+//! Creates, imports, duplicates, renames and config updates that would introduce a
+//! new route or database/table collision (see `GET /apps/conflicts`) are refused with 409.
+//!
+//! The app list is served from an in-memory inventory; each entry is rebuilt only
+//! when the mtime of one of the files it was derived from changes. The list accepts
+//! `enabled`, `is_extension`, `q` (name/id substring), `sort`, `order`, `offset`
+//! and `limit` query parameters and reports the unpaginated count in `X-Total-Count`.
+//!
+//! Disk usage (`disk_usage` on list/detail responses) is computed on a background
+//! thread and cached for `USAGE_TTL_SECS`; until the first result is ready the
+//! field reports `"status": "pending"`.
+
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+use yeti_core::prelude::*;
+
+use super::files::{backup_file, write_atomic, FILE_WRITE_LOCK};
+
+pub type Apps = AppsResource;
+
+#[derive(Default)]
+pub struct AppsResource;
+
+/// Directories that are never copied or exported (build artifacts and VCS state)
+const ARTIFACT_DIRS: &[&str] = &["node_modules", ".git", "target"];
+
+/// Maximum accepted size of an uploaded app archive (compressed); exports are capped
+/// at the same size so every exported archive can be imported again
+const MAX_IMPORT_BYTES: usize = 256 * 1024 * 1024;
+
+/// Maximum total size of the files in an imported archive once decompressed
+const MAX_UNPACKED_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Maximum number of entries in an imported archive
+const MAX_ARCHIVE_ENTRIES: usize = 100_000;
+
+/// `app_id` of this admin app in its own config.yaml, which holds the admin settings
+const ADMIN_APP_ID: &str = "admin";
+
+/// Hidden directory (inside the applications dir) holding deleted apps
+const TRASH_DIR: &str = ".trash";
+
+/// Days a deleted app is kept when `admin.trash.retention_days` is not set
+const DEFAULT_TRASH_RETENTION_DAYS: u64 = 30;
+
+/// Names that collide with sub-routes of /apps and cannot be used as app IDs
+const RESERVED_APP_IDS: &[&str] = &["bulk", "conflicts", "import", "routes", "templates", "trash", "usage", "validate-config"];
+
+/// Seconds a computed disk usage result is served before being recomputed
+const USAGE_TTL_SECS: u64 = 300;
+
+/// Default build timeout (`admin.build.timeout_secs` overrides)
+const DEFAULT_BUILD_TIMEOUT_SECS: u64 = 600;
+
+/// Log lines kept per build job (older lines are dropped, ids keep counting)
+const MAX_BUILD_LOG_LINES: usize = 5000;
+
+/// Finished build jobs kept per app
+const MAX_BUILD_JOBS_PER_APP: usize = 20;
+
+/// Default compile-check timeout (`admin.check.timeout_secs` overrides)
+const DEFAULT_CHECK_TIMEOUT_SECS: u64 = 180;
+
+/// Top-level app directories reported separately from source in disk usage
+const USAGE_SPLIT_DIRS: &[&str] = &["web", "node_modules", "target"];
+
+/// Most apps accepted by one bulk request
+const MAX_BULK_APPS: usize = 100;
+
+/// Default and maximum number of apps processed at once by a bulk request
+const DEFAULT_BULK_CONCURRENCY: usize = 4;
+const MAX_BULK_CONCURRENCY: usize = 16;
+
+/// Seconds a bulk `git pull` may take per app
+const BULK_PULL_TIMEOUT_SECS: u64 = 120;
+
+/// Manifest file describing a registered template
+const TEMPLATE_MANIFEST: &str = "template.yaml";
+
+/// Recursively copy a template directory, skipping build artifacts
+fn copy_template(src: &Path, dst: &Path) -> std::io::Result<()> {
+    const SKIP_DIRS: &[&str] = &["source", "test"];
+    const SKIP_FILES: &[&str] = &["Cargo.toml", "build.rs", ".gitignore"];
+
+    copy_dir_filtered(src, dst, SKIP_DIRS, SKIP_FILES)
+}
+
+/// Recursively copy a directory, skipping build artifacts and the given names.
+/// Symlinks are recreated as symlinks, never followed.
+fn copy_dir_filtered(src: &Path, dst: &Path, skip_dirs: &[&str], skip_files: &[&str]) -> std::io::Result<()> {
+    std::fs::create_dir_all(dst)?;
+
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let name = file_name.to_string_lossy();
+        let src_path = entry.path();
+        let dst_path = dst.join(&file_name);
+        let file_type = entry.file_type()?;
+
+        if file_type.is_symlink() {
+            if skip_files.contains(&name.as_ref()) || skip_dirs.contains(&name.as_ref()) {
+                continue;
+            }
+            std::os::unix::fs::symlink(std::fs::read_link(&src_path)?, &dst_path)?;
+        } else if file_type.is_dir() {
+            if ARTIFACT_DIRS.contains(&name.as_ref()) || skip_dirs.contains(&name.as_ref()) {
+                continue;
+            }
+            copy_dir_filtered(&src_path, &dst_path, skip_dirs, skip_files)?;
+        } else {
+            if skip_files.contains(&name.as_ref()) {
+                continue;
+            }
+            std::fs::copy(&src_path, &dst_path)?;
+        }
+    }
+    Ok(())
+}
+
+// ── Template registry ──
+//
+// Templates live in `<root>/templates/<id>/`, each with a `template.yaml`
+// manifest declaring its name, description and variables. `{{variable}}`
+// placeholders are substituted in file contents and file names. The built-in
+// `blank` and `application-template` templates are always listed.
+
+/// Directory holding registered app templates
+fn templates_directory() -> PathBuf {
+    get_root_directory().join("templates")
+}
+
+/// A variable declared in a template manifest
+struct TemplateVariable {
+    name: String,
+    description: String,
+    /// identifier, string, integer or boolean
+    kind: String,
+    default: Option<String>,
+    required: bool,
+    choices: Vec<String>,
+    max_length: Option<usize>,
+}
+
+/// Parsed `template.yaml`
+struct TemplateManifest {
+    id: String,
+    name: String,
+    description: String,
+    variables: Vec<TemplateVariable>,
+}
+
+/// Variables every template receives without declaring them
+const BUILTIN_TEMPLATE_VARIABLES: &[&str] = &["app_id", "name", "description"];
+
+/// Render a scalar JSON value as template variable text
+fn variable_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+fn read_template_manifest(template_path: &Path, id: &str) -> std::result::Result<TemplateManifest, String> {
+    let content = std::fs::read_to_string(template_path.join(TEMPLATE_MANIFEST))
+        .map_err(|e| format!("Cannot read {} for template '{}': {}", TEMPLATE_MANIFEST, id, e))?;
+    let yaml: serde_yaml::Value = serde_yaml::from_str(&content)
+        .map_err(|e| format!("Invalid {} for template '{}': {}", TEMPLATE_MANIFEST, id, e))?;
+    let manifest = serde_json::to_value(yaml).map_err(|e| e.to_string())?;
+
+    let mut variables = Vec::new();
+    for (i, var) in manifest.get("variables").and_then(|v| v.as_array()).into_iter().flatten().enumerate() {
+        let name = var.get("name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| format!("Template '{}': variable {} has no name", id, i))?;
+        let kind = var.get("type").and_then(|v| v.as_str()).unwrap_or("string");
+        if !["identifier", "string", "integer", "boolean"].contains(&kind) {
+            return Err(format!("Template '{}': variable '{}' has unknown type '{}'", id, name, kind));
+        }
+        variables.push(TemplateVariable {
+            name: name.to_string(),
+            description: var.get("description").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+            kind: kind.to_string(),
+            default: var.get("default").and_then(variable_text),
+            required: var.get("required").and_then(|v| v.as_bool()).unwrap_or(false),
+            choices: var.get("choices")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(variable_text).collect())
+                .unwrap_or_default(),
+            max_length: var.get("max_length").and_then(|v| v.as_u64()).map(|n| n as usize),
+        });
+    }
+
+    Ok(TemplateManifest {
+        id: id.to_string(),
+        name: manifest.get("name").and_then(|v| v.as_str()).unwrap_or(id).to_string(),
+        description: manifest.get("description").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+        variables,
+    })
+}
+
+fn template_json(manifest: &TemplateManifest, builtin: bool) -> serde_json::Value {
+    let variables: Vec<serde_json::Value> = manifest.variables.iter().map(|v| json!({
+        "name": v.name,
+        "description": v.description,
+        "type": v.kind,
+        "default": v.default,
+        "required": v.required,
+        "choices": v.choices,
+        "max_length": v.max_length,
+    })).collect();
+
+    json!({
+        "id": manifest.id,
+        "name": manifest.name,
+        "description": manifest.description,
+        "builtin": builtin,
+        "variables": variables,
+    })
+}
+
+/// All templates available to the New App modal
+fn list_templates() -> Vec<serde_json::Value> {
+    let builtin = |id: &str, name: &str, description: &str| TemplateManifest {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: description.to_string(),
+        variables: Vec::new(),
+    };
+
+    let mut templates = vec![template_json(&builtin("blank", "Blank", "Empty app with a resources folder and static web page"), true)];
+    if get_apps_directory().join("application-template").is_dir() {
+        templates.push(template_json(&builtin("application-template", "Application Template", "Copy of the installed application-template app"), true));
+    }
+
+    let mut registered = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(templates_directory()) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let id = entry.file_name().to_string_lossy().to_string();
+            if id.starts_with('.') || !path.join(TEMPLATE_MANIFEST).is_file() {
+                continue;
+            }
+            match read_template_manifest(&path, &id) {
+                Ok(manifest) => registered.push(template_json(&manifest, false)),
+                Err(e) => {
+                    yeti_log!(warn, "Skipping template '{}': {}", id, e);
+                }
+            }
+        }
+    }
+    registered.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+    templates.extend(registered);
+    templates
+}
+
+/// Resolve declared variables from the request, applying defaults and validation.
+/// Errors are returned per variable.
+fn resolve_template_variables(
+    manifest: &TemplateManifest,
+    provided: Option<&serde_json::Map<String, serde_json::Value>>,
+    builtins: &[(&str, &str)],
+) -> std::result::Result<std::collections::HashMap<String, String>, Vec<serde_json::Value>> {
+    let mut values: std::collections::HashMap<String, String> = builtins.iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    let mut errors = Vec::new();
+
+    for var in &manifest.variables {
+        if BUILTIN_TEMPLATE_VARIABLES.contains(&var.name.as_str()) {
+            continue;
+        }
+        let value = match provided.and_then(|p| p.get(&var.name)) {
+            Some(v) => match variable_text(v) {
+                Some(text) => Some(text),
+                None => {
+                    errors.push(json!({"variable": var.name, "message": "must be a string, number or boolean"}));
+                    continue;
+                }
+            },
+            None => var.default.clone(),
+        };
+        let Some(value) = value.filter(|v| !v.is_empty()) else {
+            if var.required {
+                errors.push(json!({"variable": var.name, "message": "is required"}));
+            } else {
+                values.insert(var.name.clone(), String::new());
+            }
+            continue;
+        };
+
+        let problem = match var.kind.as_str() {
+            "identifier" if validate_identifier(&value, &var.name).is_err() => Some("must be a valid identifier".to_string()),
+            "integer" if value.parse::<i64>().is_err() => Some("must be an integer".to_string()),
+            "boolean" if value != "true" && value != "false" => Some("must be true or false".to_string()),
+            _ if !var.choices.is_empty() && !var.choices.contains(&value) => Some(format!("must be one of: {}", var.choices.join(", "))),
+            _ if var.max_length.is_some_and(|max| value.chars().count() > max) => {
+                Some(format!("must be at most {} characters", var.max_length.unwrap_or_default()))
+            }
+            _ => None,
+        };
+        match problem {
+            Some(message) => errors.push(json!({"variable": var.name, "message": message})),
+            None => {
+                values.insert(var.name.clone(), value);
+            }
+        }
+    }
+
+    if let Some(provided) = provided {
+        for name in provided.keys() {
+            if !manifest.variables.iter().any(|v| &v.name == name) && !BUILTIN_TEMPLATE_VARIABLES.contains(&name.as_str()) {
+                errors.push(json!({"variable": name, "message": "is not declared by this template"}));
+            }
+        }
+    }
+
+    if errors.is_empty() { Ok(values) } else { Err(errors) }
+}
+
+/// Replace `{{name}}` (or `{{ name }}`) placeholders; unknown names are left as-is
+fn substitute_variables(text: &str, values: &std::collections::HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find("{{") {
+        let Some(close) = rest[open + 2..].find("}}") else { break };
+        let name = rest[open + 2..open + 2 + close].trim();
+        out.push_str(&rest[..open]);
+        match values.get(name) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&rest[open..open + 4 + close]),
+        }
+        rest = &rest[open + 4 + close..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Placeholder stand-in while a YAML template is parsed: plain, quotable and unique.
+/// A placeholder written inside quotes gets the `str` form, so it stays a string.
+fn template_sentinel(index: usize, quoted: bool) -> String {
+    format!("__yeti_template_{}_{}__", if quoted { "str" } else { "var" }, index)
+}
+
+/// Substitute variables into a YAML document (a template's config.yaml) without
+/// letting a value change its structure. Placeholders are swapped for sentinels,
+/// the document is parsed, and the values are set on the parsed scalars, so the
+/// changed nodes are re-rendered with whatever quoting YAML needs. An unquoted
+/// placeholder that is the whole scalar keeps a number, boolean or empty value typed.
+fn substitute_yaml_variables(text: &str, values: &std::collections::HashMap<String, String>) -> std::result::Result<String, String> {
+    let names: Vec<&String> = values.keys().collect();
+    let mut marked = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find("{{") {
+        let Some(close) = rest[open + 2..].find("}}") else { break };
+        let end = open + 4 + close;
+        let name = rest[open + 2..open + 2 + close].trim();
+        marked.push_str(&rest[..open]);
+        match names.iter().position(|n| n.as_str() == name) {
+            Some(index) => {
+                let before = marked.chars().last();
+                let quoted = matches!(before, Some('"' | '\'')) && rest[end..].starts_with(before.unwrap_or_default());
+                marked.push_str(&template_sentinel(index, quoted));
+            }
+            None => marked.push_str(&rest[open..end]),
+        }
+        rest = &rest[end..];
+    }
+    marked.push_str(rest);
+
+    let old: serde_json::Value = serde_yaml::from_str::<serde_yaml::Value>(&marked)
+        .ok()
+        .and_then(|yaml| serde_json::to_value(yaml).ok())
+        .ok_or_else(|| "template config.yaml is not valid YAML".to_string())?;
+
+    let fill = |s: &str, one_line: bool| names.iter().enumerate().fold(s.to_string(), |acc, (i, name)| {
+        let value = if one_line { values[*name].replace(['\r', '\n'], " ") } else { values[*name].clone() };
+        acc.replace(&template_sentinel(i, false), &value).replace(&template_sentinel(i, true), &value)
+    });
+    let scalar = |s: &str| -> serde_json::Value {
+        let typed = names.iter()
+            .enumerate()
+            .find(|(i, _)| s == template_sentinel(*i, false))
+            .and_then(|(_, name)| serde_yaml::from_str::<serde_yaml::Value>(&values[*name]).ok())
+            .filter(|v| v.is_bool() || v.is_number() || v.is_null());
+        match typed {
+            Some(typed) => serde_json::to_value(typed).unwrap_or_default(),
+            None => json!(fill(s, false)),
+        }
+    };
+    fn fill_strings(value: &serde_json::Value, scalar: &dyn Fn(&str) -> serde_json::Value, key: &dyn Fn(&str) -> String) -> serde_json::Value {
+        match value {
+            serde_json::Value::String(s) => scalar(s),
+            serde_json::Value::Array(items) => items.iter().map(|v| fill_strings(v, scalar, key)).collect(),
+            serde_json::Value::Object(map) => map.iter()
+                .map(|(k, v)| (key(k), fill_strings(v, scalar, key)))
+                .collect::<serde_json::Map<_, _>>()
+                .into(),
+            other => other.clone(),
+        }
+    }
+    let new = fill_strings(&old, &scalar, &|k| fill(k, false));
+
+    // Whatever sentinels remain sit in comments; keep those values on one line
+    let (edited, _) = edit_yaml_preserving(&marked, &old, &new);
+    let edited = fill(&edited, true);
+    if yaml_matches(&edited, &new) {
+        Ok(edited)
+    } else {
+        serde_yaml::to_string(&new).map_err(|e| e.to_string())
+    }
+}
+
+/// Copy a registered template, substituting variables in names and text contents.
+/// The root config.yaml is filled in as YAML, other files as plain text. Symlinks are copied as links.
+fn copy_template_with_variables(
+    src: &Path,
+    dst: &Path,
+    values: &std::collections::HashMap<String, String>,
+    is_root: bool,
+) -> std::io::Result<()> {
+    std::fs::create_dir_all(dst)?;
+
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let name = file_name.to_string_lossy();
+        let src_path = entry.path();
+
+        if is_root && name == TEMPLATE_MANIFEST {
+            continue;
+        }
+        let target_name = substitute_variables(&name, values);
+        if target_name.is_empty() || target_name == "." || target_name == ".." || target_name.contains(['/', '\\']) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("'{}' expands to an invalid file name '{}'", name, target_name),
+            ));
+        }
+        let dst_path = dst.join(&target_name);
+        let file_type = entry.file_type()?;
+
+        // Links are recreated as links, never followed, so a template can't pull in files from outside it
+        if file_type.is_symlink() {
+            std::os::unix::fs::symlink(std::fs::read_link(&src_path)?, &dst_path)?;
+        } else if file_type.is_dir() {
+            if ARTIFACT_DIRS.contains(&name.as_ref()) {
+                continue;
+            }
+            copy_template_with_variables(&src_path, &dst_path, values, false)?;
+        } else {
+            let bytes = std::fs::read(&src_path)?;
+            match String::from_utf8(bytes) {
+                Ok(text) if is_root && name == "config.yaml" => {
+                    let filled = substitute_yaml_variables(&text, values)
+                        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+                    write_atomic(&dst_path, filled.as_bytes())?
+                }
+                Ok(text) => write_atomic(&dst_path, substitute_variables(&text, values).as_bytes())?,
+                Err(e) => write_atomic(&dst_path, e.as_bytes())?,
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Split the request path into the segments that follow `/apps`
+/// e.g. /admin/apps/my-app/export -> ["my-app", "export"]
+fn route_segments(uri_path: &str) -> Vec<&str> {
+    let segments: Vec<&str> = uri_path.split('/').filter(|s| !s.is_empty()).collect();
+    match segments.iter().position(|s| *s == "apps") {
+        Some(pos) => segments[pos + 1..].to_vec(),
+        None => Vec::new(),
+    }
+}
+
+/// Write a gzipped tarball of an app directory to `out`, with entries rooted at `{app_id}/`.
+/// Files are read and compressed one at a time, so only the output grows with the app.
+fn build_app_archive<W: std::io::Write>(out: W, app_path: &Path, app_id: &str) -> std::io::Result<W> {
+    let encoder = flate2::write::GzEncoder::new(out, flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    builder.follow_symlinks(false);
+    append_archive_dir(&mut builder, app_path, Path::new(app_id))?;
+    builder.into_inner()?.finish()
+}
+
+/// In-memory sink that fails once more than `limit` bytes are written
+struct CappedBuffer {
+    bytes: Vec<u8>,
+    limit: usize,
+}
+
+impl std::io::Write for CappedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.bytes.len() + buf.len() > self.limit {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::OutOfMemory,
+                format!("archive exceeds {} byte limit", self.limit),
+            ));
+        }
+        self.bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Recursively append a directory to an archive, skipping build artifacts and symlinks
+fn append_archive_dir<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    src: &Path,
+    prefix: &Path,
+) -> std::io::Result<()> {
+    let mut entries: Vec<_> = std::fs::read_dir(src)?.flatten().collect();
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let file_name = entry.file_name();
+        let name = file_name.to_string_lossy();
+        let file_type = entry.file_type()?;
+        let src_path = entry.path();
+        let archive_path = prefix.join(&file_name);
+
+        if file_type.is_dir() {
+            if ARTIFACT_DIRS.contains(&name.as_ref()) {
+                continue;
+            }
+            builder.append_dir(&archive_path, &src_path)?;
+            append_archive_dir(builder, &src_path, &archive_path)?;
+        } else if file_type.is_file() {
+            builder.append_path_with_name(&src_path, &archive_path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Normalise an archive entry path, rejecting absolute paths and `..` components
+fn safe_archive_path(path: &Path) -> std::result::Result<PathBuf, String> {
+    let mut clean = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => clean.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                return Err(format!("Archive entry '{}' contains '..'", path.display()));
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(format!("Archive entry '{}' is an absolute path", path.display()));
+            }
+        }
+    }
+    Ok(clean)
+}
+
+/// Validated contents of an uploaded app archive
+struct ArchiveManifest {
+    /// Leading directory shared by every entry (stripped on unpack), if any
+    root: Option<PathBuf>,
+    /// Raw config.yaml content
+    config: String,
+}
+
+/// Walk an archive once, checking every entry and locating its config.yaml.
+/// Only regular files and directories are accepted, and the entry count and
+/// unpacked size are capped, so a small upload can't expand without bound.
+fn inspect_archive(bytes: &[u8]) -> std::result::Result<ArchiveManifest, String> {
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(bytes));
+    let entries = archive.entries().map_err(|e| format!("Invalid archive: {}", e))?;
+
+    let mut paths: Vec<PathBuf> = Vec::new();
+    let mut configs: Vec<(PathBuf, String)> = Vec::new();
+    let mut unpacked_bytes: u64 = 0;
+
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Invalid archive entry: {}", e))?;
+        if paths.len() >= MAX_ARCHIVE_ENTRIES {
+            return Err(format!("Archive has more than {} entries", MAX_ARCHIVE_ENTRIES));
+        }
+        unpacked_bytes = unpacked_bytes.saturating_add(entry.size());
+        if unpacked_bytes > MAX_UNPACKED_BYTES {
+            return Err(format!("Archive unpacks to more than {} bytes", MAX_UNPACKED_BYTES));
+        }
+        let raw_path = entry.path().map_err(|e| format!("Invalid archive path: {}", e))?.into_owned();
+        let path = safe_archive_path(&raw_path)?;
+
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_symlink() || entry_type.is_hard_link() {
+            return Err(format!("Archive entry '{}' is a link, links are not allowed", raw_path.display()));
+        }
+        if !entry_type.is_file() && !entry_type.is_dir() {
+            return Err(format!("Archive entry '{}' has an unsupported type", raw_path.display()));
+        }
+
+        if entry_type.is_file() && path.file_name().is_some_and(|n| n == "config.yaml") && path.components().count() <= 2 {
+            let mut content = String::new();
+            entry.read_to_string(&mut content)
+                .map_err(|e| format!("config.yaml is not valid UTF-8: {}", e))?;
+            configs.push((path.clone(), content));
+        }
+        paths.push(path);
+    }
+
+    // Accept either config.yaml at the archive root or a single wrapping directory
+    if let Some((_, content)) = configs.iter().find(|(p, _)| p.components().count() == 1) {
+        return Ok(ArchiveManifest { root: None, config: content.clone() });
+    }
+    let (config_path, content) = configs.into_iter().next()
+        .ok_or_else(|| "Archive does not contain a config.yaml".to_string())?;
+    let root = config_path.parent().map(Path::to_path_buf).unwrap_or_default();
+    if paths.iter().any(|p| !p.as_os_str().is_empty() && !p.starts_with(&root)) {
+        return Err("Archive must contain a single application directory".to_string());
+    }
+    Ok(ArchiveManifest { root: Some(root), config: content })
+}
+
+/// Unpack a previously inspected archive into `dst`, stripping the manifest root.
+/// The inspection limits are enforced again while writing.
+fn unpack_archive(bytes: &[u8], manifest: &ArchiveManifest, dst: &Path) -> std::result::Result<(), String> {
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(bytes));
+    let entries = archive.entries().map_err(|e| format!("Invalid archive: {}", e))?;
+
+    std::fs::create_dir_all(dst).map_err(|e| format!("Failed to create app directory: {}", e))?;
+
+    let mut unpacked_bytes: u64 = 0;
+    for (index, entry) in entries.enumerate() {
+        let mut entry = entry.map_err(|e| format!("Invalid archive entry: {}", e))?;
+        if index >= MAX_ARCHIVE_ENTRIES {
+            return Err(format!("Archive has more than {} entries", MAX_ARCHIVE_ENTRIES));
+        }
+        unpacked_bytes = unpacked_bytes.saturating_add(entry.size());
+        if unpacked_bytes > MAX_UNPACKED_BYTES {
+            return Err(format!("Archive unpacks to more than {} bytes", MAX_UNPACKED_BYTES));
+        }
+        let raw_path = entry.path().map_err(|e| format!("Invalid archive path: {}", e))?.into_owned();
+        let path = safe_archive_path(&raw_path)?;
+        let rel = match &manifest.root {
+            Some(root) => path.strip_prefix(root).map(Path::to_path_buf).unwrap_or_default(),
+            None => path,
+        };
+        if rel.as_os_str().is_empty() {
+            continue;
+        }
+
+        let target = dst.join(&rel);
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_dir() {
+            std::fs::create_dir_all(&target)
+                .map_err(|e| format!("Failed to create '{}': {}", rel.display(), e))?;
+        } else if entry_type.is_file() {
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create '{}': {}", rel.display(), e))?;
+            }
+            entry.unpack(&target)
+                .map_err(|e| format!("Failed to unpack '{}': {}", rel.display(), e))?;
+        } else {
+            return Err(format!("Archive entry '{}' has an unsupported type", raw_path.display()));
+        }
+    }
+    Ok(())
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Reject app IDs that would shadow a sub-route of /apps
+fn check_reserved_id(app_id: &str) -> Result<()> {
+    if RESERVED_APP_IDS.contains(&app_id) {
+        return Err(YetiError::Validation(format!("'{}' is a reserved name and cannot be used as an app_id", app_id)));
+    }
+    Ok(())
+}
+
+/// Directory of this admin app: the one whose config.yaml says `app_id: admin`.
+/// It need not be named `admin` (the README installs it as `yeti-admin`).
+pub(crate) fn admin_app_dir() -> Option<String> {
+    static RESOLVED: Mutex<Option<String>> = Mutex::new(None);
+    let apps_path = get_apps_directory();
+    let is_admin = |dir: &str| {
+        read_app_config(&apps_path.join(dir)).is_some_and(|c| c["app_id"].as_str() == Some(ADMIN_APP_ID))
+    };
+
+    let mut resolved = RESOLVED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(dir) = resolved.as_deref().filter(|dir| is_admin(dir)) {
+        return Some(dir.to_string());
+    }
+    let found = if is_admin(ADMIN_APP_ID) {
+        Some(ADMIN_APP_ID.to_string())
+    } else {
+        let mut dirs: Vec<String> = std::fs::read_dir(&apps_path).ok()?
+            .flatten()
+            .filter_map(|e| e.file_name().to_str().map(String::from))
+            .filter(|name| !name.starts_with('.'))
+            .collect();
+        dirs.sort();
+        dirs.into_iter().find(|dir| is_admin(dir))
+    };
+    resolved.clone_from(&found);
+    found
+}
+
+/// Read the `admin:` settings block from this app's own config.yaml
+pub(crate) fn admin_settings() -> serde_json::Value {
+    admin_app_dir()
+        .and_then(|dir| read_app_config(&get_apps_directory().join(dir)))
+        .and_then(|c| c.get("admin").cloned())
+        .unwrap_or(serde_json::Value::Null)
+}
+
+/// Days to keep deleted apps before purging them (0 keeps them forever)
+fn trash_retention_days() -> u64 {
+    admin_settings()
+        .pointer("/trash/retention_days")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS)
+}
+
+/// How strongly an app is protected against deletion (`admin.protection` in config.yaml)
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Protection {
+    None,
+    /// Deletable only when the request carries `confirm=<app_id>`
+    Confirm,
+    Never,
+}
+
+impl Protection {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "none" => Some(Protection::None),
+            "confirm" => Some(Protection::Confirm),
+            "never" => Some(Protection::Never),
+            _ => None,
+        }
+    }
+}
+
+/// Protection level for an app: explicit `admin.protection.apps.<id>` entry first,
+/// then `admin.protection.extensions` (default `confirm`) for extension apps.
+/// The admin app itself can never be deleted.
+pub(crate) fn protection_level(app_id: &str) -> Protection {
+    if admin_app_dir().as_deref() == Some(app_id) {
+        return Protection::Never;
+    }
+    let settings = admin_settings();
+    let policy = settings.get("protection");
+    if let Some(level) = policy
+        .and_then(|p| p.get("apps"))
+        .and_then(|apps| apps.get(app_id))
+        .and_then(|v| v.as_str())
+        .and_then(Protection::parse)
+    {
+        return level;
+    }
+
+    let is_extension = read_app_config(&get_apps_directory().join(app_id))
+        .and_then(|c| c.get("extension").and_then(|v| v.as_bool()))
+        .unwrap_or(false);
+    if is_extension {
+        return policy
+            .and_then(|p| p.get("extensions"))
+            .and_then(|v| v.as_str())
+            .and_then(Protection::parse)
+            .unwrap_or(Protection::Confirm);
+    }
+    Protection::None
+}
+
+/// 403 body when the protection policy refuses a destructive action on `app_id`;
+/// `action` completes "cannot be ..." in the message (e.g. "deleted")
+pub(crate) fn protection_rejection(app_id: &str, action: &str, confirm: Option<&str>) -> Option<serde_json::Value> {
+    match protection_level(app_id) {
+        Protection::None => None,
+        Protection::Never => Some(json!({
+            "error": format!("Application '{}' is protected and cannot be {}", app_id, action),
+            "protection": "never",
+        })),
+        Protection::Confirm if confirm == Some(app_id) => None,
+        Protection::Confirm => Some(json!({
+            "error": format!("Application '{}' is protected; repeat the request with confirm={}", app_id, app_id),
+            "protection": "confirm",
+            "confirm": app_id,
+        })),
+    }
+}
+
+/// Split a trash entry name `<app_id>-<timestamp>` into its parts
+fn parse_trash_entry(entry: &str) -> Option<(&str, u64)> {
+    let (app_id, timestamp) = entry.rsplit_once('-')?;
+    if app_id.is_empty() || app_id.starts_with('.') {
+        return None;
+    }
+    Some((app_id, timestamp.parse().ok()?))
+}
+
+/// Move an app directory into the trash, returning the trash entry name
+fn move_to_trash(apps_path: &Path, app_id: &str) -> std::io::Result<String> {
+    let trash_path = apps_path.join(TRASH_DIR);
+    std::fs::create_dir_all(&trash_path)?;
+
+    let entry = format!("{}-{}", app_id, now_secs());
+    std::fs::rename(apps_path.join(app_id), trash_path.join(&entry))?;
+    // Secrets left under the id would pass to the next app created with it
+    if let Err(e) = super::secrets::trash_app_secrets(app_id, &entry) {
+        if let Err(restore) = std::fs::rename(trash_path.join(&entry), apps_path.join(app_id)) {
+            yeti_log!(error, "Delete rollback failed for '{}': {}", app_id, restore);
+        }
+        return Err(std::io::Error::other(e));
+    }
+    if let Err(e) = super::files::trash_app_backups(app_id, &entry) {
+        yeti_log!(warn, "Failed to move backups of '{}' to the trash: {}", app_id, e);
+    }
+    Ok(entry)
+}
+
+/// Permanently remove trash entries older than the configured retention
+fn purge_expired_trash(apps_path: &Path) {
+    let retention_days = trash_retention_days();
+    if retention_days == 0 {
+        return;
+    }
+    let cutoff = now_secs().saturating_sub(retention_days * 24 * 60 * 60);
+
+    let Ok(entries) = std::fs::read_dir(apps_path.join(TRASH_DIR)) else { return };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some((_, deleted_at)) = parse_trash_entry(&name) {
+            if deleted_at < cutoff && std::fs::remove_dir_all(entry.path()).is_ok() {
+                let _ = super::files::purge_app_backups(&name);
+                if let Err(e) = super::secrets::purge_app_secrets(&name) {
+                    yeti_log!(warn, "Failed to remove secrets of trash entry '{}': {}", name, e);
+                }
+            }
+        }
+    }
+}
+
+/// List trash entries, newest first. Expired entries are left out; they are
+/// removed by the next mutating trash operation, not by reads.
+fn list_trash(apps_path: &Path) -> Vec<serde_json::Value> {
+    let retention_days = trash_retention_days();
+    let now = now_secs();
+    let mut items = Vec::new();
+
+    let Ok(entries) = std::fs::read_dir(apps_path.join(TRASH_DIR)) else { return items };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some((app_id, deleted_at)) = parse_trash_entry(&name) else { continue };
+
+        let config_name = read_app_config(&path)
+            .and_then(|c| c.get("name").and_then(|v| v.as_str()).map(String::from));
+        let expires_at = if retention_days == 0 {
+            None
+        } else {
+            Some(deleted_at + retention_days * 24 * 60 * 60)
+        };
+        if expires_at.is_some_and(|at| at <= now) {
+            continue;
+        }
+
+        items.push(json!({
+            "entry": name,
+            "app_id": app_id,
+            "name": config_name.unwrap_or_else(|| app_id.to_string()),
+            "deleted_at": deleted_at,
+            "expires_at": expires_at,
+            "id_in_use": apps_path.join(app_id).exists(),
+        }));
+    }
+
+    items.sort_by(|a, b| b["deleted_at"].as_u64().cmp(&a["deleted_at"].as_u64()));
+    items
+}
+
+/// Resolve a trash entry path from the URL, rejecting anything that isn't a valid entry name
+fn resolve_trash_entry(apps_path: &Path, entry: &str) -> Result<PathBuf> {
+    let (app_id, _) = parse_trash_entry(entry)
+        .ok_or_else(|| YetiError::Validation(format!("Invalid trash entry '{}'", entry)))?;
+    validate_identifier(app_id, "app_id")?;
+
+    Ok(apps_path.join(TRASH_DIR).join(entry))
+}
+
+/// Apply an RFC 7396 JSON Merge Patch
+fn merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    let Some(patch_map) = patch.as_object() else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = json!({});
+    }
+    if let Some(target_map) = target.as_object_mut() {
+        for (key, value) in patch_map {
+            if value.is_null() {
+                target_map.remove(key);
+            } else {
+                merge_patch(target_map.entry(key.clone()).or_insert(serde_json::Value::Null), value);
+            }
+        }
+    }
+}
+
+/// Split a JSON Pointer (RFC 6901) into unescaped reference tokens
+fn parse_pointer(pointer: &str) -> std::result::Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !pointer.starts_with('/') {
+        return Err(format!("Invalid JSON Pointer '{}'", pointer));
+    }
+    Ok(pointer[1..]
+        .split('/')
+        .map(|t| t.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+/// Escape a key for use as a JSON Pointer reference token
+fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Parse an array index token, rejecting leading zeros and out-of-range values
+fn parse_array_index(token: &str, len: usize) -> std::result::Result<usize, String> {
+    if token.is_empty() || (token.len() > 1 && token.starts_with('0')) || !token.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("Invalid array index '{}'", token));
+    }
+    let index: usize = token.parse().map_err(|_| format!("Invalid array index '{}'", token))?;
+    if index > len {
+        return Err(format!("Array index {} is out of bounds", index));
+    }
+    Ok(index)
+}
+
+/// Walk to the value referenced by `tokens`
+fn pointer_target<'a>(doc: &'a mut serde_json::Value, tokens: &[String]) -> std::result::Result<&'a mut serde_json::Value, String> {
+    let mut current = doc;
+    for token in tokens {
+        current = match current {
+            serde_json::Value::Object(map) => map.get_mut(token)
+                .ok_or_else(|| format!("Path segment '{}' does not exist", token))?,
+            serde_json::Value::Array(arr) => {
+                let index = parse_array_index(token, arr.len())?;
+                arr.get_mut(index).ok_or_else(|| format!("Array index {} is out of bounds", index))?
+            }
+            _ => return Err(format!("Path segment '{}' is not a container", token)),
+        };
+    }
+    Ok(current)
+}
+
+/// JSON Patch `add`: insert into an object or array, or replace the whole document
+fn pointer_add(doc: &mut serde_json::Value, path: &str, value: serde_json::Value) -> std::result::Result<(), String> {
+    let tokens = parse_pointer(path)?;
+    let Some((last, parents)) = tokens.split_last() else {
+        *doc = value;
+        return Ok(());
+    };
+    match pointer_target(doc, parents)? {
+        serde_json::Value::Object(map) => {
+            map.insert(last.clone(), value);
+        }
+        serde_json::Value::Array(arr) => {
+            if last == "-" {
+                arr.push(value);
+            } else {
+                let index = parse_array_index(last, arr.len())?;
+                arr.insert(index, value);
+            }
+        }
+        _ => return Err(format!("Cannot add to '{}': parent is not a container", path)),
+    }
+    Ok(())
+}
+
+/// JSON Patch `remove`: delete and return the referenced value
+fn pointer_remove(doc: &mut serde_json::Value, path: &str) -> std::result::Result<serde_json::Value, String> {
+    let tokens = parse_pointer(path)?;
+    let Some((last, parents)) = tokens.split_last() else {
+        return Err("Cannot remove the whole document".to_string());
+    };
+    match pointer_target(doc, parents)? {
+        serde_json::Value::Object(map) => map.remove(last)
+            .ok_or_else(|| format!("Cannot remove '{}': key does not exist", path)),
+        serde_json::Value::Array(arr) => {
+            let index = parse_array_index(last, arr.len())?;
+            if index >= arr.len() {
+                return Err(format!("Cannot remove '{}': index out of bounds", path));
+            }
+            Ok(arr.remove(index))
+        }
+        _ => Err(format!("Cannot remove '{}': parent is not a container", path)),
+    }
+}
+
+/// Apply an RFC 6902 JSON Patch. On error the document may be partially patched,
+/// so callers should apply it to a copy.
+fn apply_json_patch(doc: &mut serde_json::Value, patch: &serde_json::Value) -> std::result::Result<(), String> {
+    let operations = patch.as_array()
+        .ok_or_else(|| "JSON Patch must be an array of operations".to_string())?;
+
+    for (i, operation) in operations.iter().enumerate() {
+        let field = |name: &str| {
+            operation.get(name)
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| format!("Operation {}: missing '{}'", i, name))
+        };
+        let value = || {
+            operation.get("value")
+                .cloned()
+                .ok_or_else(|| format!("Operation {}: missing 'value'", i))
+        };
+        let op = field("op")?;
+        let path = field("path")?;
+
+        let result = match op {
+            "add" => pointer_add(doc, path, value()?),
+            "remove" => pointer_remove(doc, path).map(|_| ()),
+            "replace" => {
+                let value = value()?;
+                pointer_remove(doc, path).and_then(|_| pointer_add(doc, path, value))
+            }
+            "move" => {
+                let from = field("from")?;
+                if path.starts_with(&format!("{}/", from)) {
+                    return Err(format!("Operation {}: cannot move '{}' into itself", i, from));
+                }
+                pointer_remove(doc, from).and_then(|v| pointer_add(doc, path, v))
+            }
+            "copy" => {
+                let from = field("from")?;
+                let tokens = parse_pointer(from)?;
+                pointer_target(doc, &tokens)
+                    .map(|v| v.clone())
+                    .and_then(|v| pointer_add(doc, path, v))
+            }
+            "test" => {
+                let expected = value()?;
+                let tokens = parse_pointer(path)?;
+                match pointer_target(doc, &tokens) {
+                    Ok(actual) if *actual == expected => Ok(()),
+                    _ => Err(format!("test failed for '{}'", path)),
+                }
+            }
+            other => Err(format!("unknown op '{}'", other)),
+        };
+        result.map_err(|e| format!("Operation {} ({} {}): {}", i, op, path, e))?;
+    }
+    Ok(())
+}
+
+/// Describe the changes between two config values as `{op, path, from, to}`
+/// entries, where `path` is a JSON Pointer to the changed node
+fn config_diff(old: &serde_json::Value, new: &serde_json::Value) -> Vec<serde_json::Value> {
+    let mut changes = Vec::new();
+    diff_values("", old, new, &mut changes);
+    changes
+}
+
+fn diff_values(path: &str, old: &serde_json::Value, new: &serde_json::Value, changes: &mut Vec<serde_json::Value>) {
+    match (old, new) {
+        (serde_json::Value::Object(old_map), serde_json::Value::Object(new_map)) => {
+            for (key, old_value) in old_map {
+                let child = format!("{}/{}", path, escape_pointer_token(key));
+                match new_map.get(key) {
+                    Some(new_value) => diff_values(&child, old_value, new_value, changes),
+                    None => changes.push(json!({"op": "removed", "path": child, "from": old_value})),
+                }
+            }
+            for (key, new_value) in new_map {
+                if !old_map.contains_key(key) {
+                    let child = format!("{}/{}", path, escape_pointer_token(key));
+                    changes.push(json!({"op": "added", "path": child, "to": new_value}));
+                }
+            }
+        }
+        _ if old != new => {
+            changes.push(json!({"op": "changed", "path": path, "from": old, "to": new}));
+        }
+        _ => {}
+    }
+}
+
+// ── Frontend build jobs ──
+//
+// Job state lives in memory. Every log line and the final `end` event are also
+// written to the exported BuildEvent table (`{job_id}-{line:06}` and
+// `{job_id}-end`), so clients follow a build with Yeti's `?stream=sse`.
+
+/// Why a build or compile check ended without a result
+#[derive(Debug)]
+pub(crate) enum RunError {
+    /// Another check of the same app held the lock for the whole timeout
+    Busy(String),
+    /// The command ran past its timeout and was killed
+    Timeout(String),
+    /// The command could not be prepared, started or waited on
+    Failed(String),
+}
+
+impl std::fmt::Display for RunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunError::Busy(msg) | RunError::Timeout(msg) | RunError::Failed(msg) => f.write_str(msg),
+        }
+    }
+}
+
+/// Writes to the BuildEvent table: `(id, Some(record))` puts, `(id, None)` deletes
+type BuildEventSender = tokio::sync::mpsc::UnboundedSender<(String, Option<serde_json::Value>)>;
+
+/// A `static_files.build` run
+struct BuildJob {
+    id: String,
+    app_id: String,
+    command: String,
+    source_dir: String,
+    /// queued, running, succeeded, failed or timed_out
+    status: String,
+    started_at: u64,
+    duration_ms: Option<u64>,
+    exit_code: Option<i32>,
+    error: Option<String>,
+    web_usage: Option<serde_json::Value>,
+    /// Number of log lines dropped from the front of `log`
+    log_offset: usize,
+    log: Vec<String>,
+    /// Open until the job finishes
+    events: Option<BuildEventSender>,
+}
+
+impl BuildJob {
+    fn finished(&self) -> bool {
+        !matches!(self.status.as_str(), "queued" | "running")
+    }
+
+    /// Total log lines so far, including dropped ones
+    fn log_lines(&self) -> usize {
+        self.log_offset + self.log.len()
+    }
+
+    /// Queue a BuildEvent record; `line` is 1-based, `None` for the `end` event
+    fn emit(&self, line: Option<usize>, data: String) {
+        let Some(events) = &self.events else { return };
+        let id = match line {
+            Some(n) => format!("{}-{:06}", self.id, n),
+            None => format!("{}-end", self.id),
+        };
+        let record = json!({
+            "id": id,
+            "jobId": self.id,
+            "appId": self.app_id,
+            "line": line.unwrap_or(self.log_lines() + 1),
+            "event": if line.is_some() { "log" } else { "end" },
+            "data": data,
+        });
+        let _ = events.send((id, Some(record)));
+    }
+
+    fn to_json(&self, include_log: bool) -> serde_json::Value {
+        let mut job = json!({
+            "job_id": self.id,
+            "app_id": self.app_id,
+            "command": self.command,
+            "source_dir": self.source_dir,
+            "status": self.status,
+            "started_at": self.started_at,
+            "duration_ms": self.duration_ms,
+            "exit_code": self.exit_code,
+            "error": self.error,
+            "web_usage": self.web_usage,
+            "log_lines": self.log_lines(),
+        });
+        if include_log {
+            job["log"] = json!(self.log);
+        }
+        job
+    }
+}
+
+fn build_jobs() -> &'static Mutex<Vec<BuildJob>> {
+    static JOBS: OnceLock<Mutex<Vec<BuildJob>>> = OnceLock::new();
+    JOBS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+fn with_build_job<T>(job_id: &str, f: impl FnOnce(&mut BuildJob) -> T) -> Option<T> {
+    let mut jobs = build_jobs().lock().unwrap_or_else(|e| e.into_inner());
+    jobs.iter_mut().find(|j| j.id == job_id).map(f)
+}
+
+fn append_build_log(job_id: &str, line: String) {
+    with_build_job(job_id, |job| {
+        job.emit(Some(job.log_lines() + 1), line.clone());
+        job.log.push(line);
+        if job.log.len() > MAX_BUILD_LOG_LINES {
+            let excess = job.log.len() - MAX_BUILD_LOG_LINES;
+            job.log.drain(..excess);
+            job.log_offset += excess;
+        }
+    });
+}
+
+fn finish_build_job(job_id: &str, status: &str, exit_code: Option<i32>, error: Option<String>, started: std::time::Instant) {
+    with_build_job(job_id, |job| {
+        job.status = status.to_string();
+        job.exit_code = exit_code;
+        job.error = error;
+        job.duration_ms = Some(started.elapsed().as_millis() as u64);
+        job.emit(None, job.to_json(false).to_string());
+        // Dropping the sender lets the writer task finish
+        job.events = None;
+    });
+}
+
+/// Build timeout from `admin.build.timeout_secs`
+fn build_timeout_secs() -> u64 {
+    admin_settings()
+        .pointer("/build/timeout_secs")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_BUILD_TIMEOUT_SECS)
+}
+
+/// Forward each line of a child's output stream into the job log
+fn pipe_build_output(job_id: String, stream: impl Read + Send + 'static, prefix: &'static str) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        use std::io::BufRead;
+        for line in std::io::BufReader::new(stream).lines().map_while(|l| l.ok()) {
+            append_build_log(&job_id, format!("{}{}", prefix, line));
+        }
+    })
+}
+
+/// Run a build job to completion on the current thread
+fn run_build_job(job_id: String, source_path: PathBuf, web_path: PathBuf, command: String, timeout: std::time::Duration) {
+    use std::os::unix::process::CommandExt;
+
+    let started = std::time::Instant::now();
+    with_build_job(&job_id, |job| job.status = "running".to_string());
+
+    let spawned = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .current_dir(&source_path)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .process_group(0)
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => {
+            finish_build_job(&job_id, "failed", None, Some(format!("Failed to start build: {}", e)), started);
+            return;
+        }
+    };
+
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(pipe_build_output(job_id.clone(), stdout, ""));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(pipe_build_output(job_id.clone(), stderr, "[stderr] "));
+    }
+
+    let outcome = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Ok(status),
+            Ok(None) if started.elapsed() > timeout => {
+                // Kill the whole process group so npm's children go too
+                let _ = std::process::Command::new("kill")
+                    .args(["-KILL", "--", &format!("-{}", child.id())])
+                    .status();
+                let _ = child.kill();
+                let _ = child.wait();
+                break Err(RunError::Timeout(format!("Build timed out after {}s", timeout.as_secs())));
+            }
+            Ok(None) => std::thread::sleep(std::time::Duration::from_millis(100)),
+            Err(e) => break Err(RunError::Failed(format!("Failed to wait for build: {}", e))),
+        }
+    };
+    for reader in readers {
+        let _ = reader.join();
+    }
+
+    match outcome {
+        Ok(status) if status.success() => {
+            let (bytes, files) = dir_usage(&web_path);
+            with_build_job(&job_id, |job| job.web_usage = Some(usage_json(bytes, files)));
+            finish_build_job(&job_id, "succeeded", status.code(), None, started);
+        }
+        Ok(status) => {
+            finish_build_job(&job_id, "failed", status.code(), Some(format!("Build exited with {}", status)), started);
+        }
+        Err(e) => {
+            let status = if matches!(e, RunError::Timeout(_)) { "timed_out" } else { "failed" };
+            finish_build_job(&job_id, status, None, Some(e.to_string()), started);
+        }
+    }
+    yeti_log!(info, "Build job {} finished", job_id);
+}
+
+/// Register a build job and start it in the background. Fails if one is already running for the app.
+/// `events` receives the job's BuildEvent records and the removal of expired jobs' records.
+fn start_build_job(
+    app_id: &str,
+    source_path: PathBuf,
+    web_path: PathBuf,
+    command: String,
+    source_dir: String,
+    timeout_secs: u64,
+    events: BuildEventSender,
+) -> std::result::Result<String, String> {
+    let job_id = format!("{}-{}", app_id, std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0));
+    {
+        let mut jobs = build_jobs().lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(running) = jobs.iter().find(|j| j.app_id == app_id && !j.finished()) {
+            return Err(running.id.clone());
+        }
+
+        // Every earlier job for this app has finished; drop the oldest beyond the limit
+        let previous: Vec<String> = jobs.iter()
+            .filter(|j| j.app_id == app_id)
+            .map(|j| j.id.clone())
+            .collect();
+        if previous.len() >= MAX_BUILD_JOBS_PER_APP {
+            let expired = &previous[..previous.len() + 1 - MAX_BUILD_JOBS_PER_APP];
+            for job in jobs.iter().filter(|j| expired.contains(&j.id)) {
+                for line in 1..=job.log_lines() {
+                    let _ = events.send((format!("{}-{:06}", job.id, line), None));
+                }
+                let _ = events.send((format!("{}-end", job.id), None));
+            }
+            jobs.retain(|j| !expired.contains(&j.id));
+        }
+
+        jobs.push(BuildJob {
+            id: job_id.clone(),
+            app_id: app_id.to_string(),
+            command: command.clone(),
+            source_dir,
+            status: "queued".to_string(),
+            started_at: now_secs(),
+            duration_ms: None,
+            exit_code: None,
+            error: None,
+            web_usage: None,
+            log_offset: 0,
+            log: Vec::new(),
+            events: Some(events),
+        });
+    }
+
+    let id = job_id.clone();
+    std::thread::spawn(move || {
+        run_build_job(id, source_path, web_path, command, std::time::Duration::from_secs(timeout_secs));
+    });
+    Ok(job_id)
+}
+
+// ── Compile checks ──
+
+/// Compile-check timeout from `admin.check.timeout_secs`
+fn check_timeout_secs() -> u64 {
+    admin_settings()
+        .pointer("/check/timeout_secs")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_CHECK_TIMEOUT_SECS)
+}
+
+/// Crate a check runs against: a private copy of Yeti's generated plugin crate
+/// (cache dir) or of the app itself when it has its own Cargo.toml
+struct CheckCrate {
+    dir: PathBuf,
+    /// Whether `dir` mirrors the app root, so crate paths are app paths
+    mirrors_app: bool,
+}
+
+/// Copy the crate to check into a private `.check-{app_id}` directory next to it
+/// (so relative path dependencies still resolve) and bring it up to date with
+/// the app's current sources. Yeti's cached crate can predate the last save.
+fn prepare_check_crate(app_id: &str, app_path: &Path) -> std::result::Result<CheckCrate, String> {
+    let cached = get_cache_directory().join(app_id);
+    let (source, mirrors_app) = if cached.join("Cargo.toml").is_file() {
+        (cached, false)
+    } else if app_path.join("Cargo.toml").is_file() {
+        (app_path.to_path_buf(), true)
+    } else {
+        return Err(format!("No compiled crate found for '{}'; load the app in Yeti once first", app_id));
+    };
+
+    let parent = source.parent().ok_or("Crate directory has no parent")?;
+    let dir = parent.join(format!(".check-{}", app_id));
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).map_err(|e| format!("Cannot clear check dir: {}", e))?;
+    }
+    copy_dir_filtered(&source, &dir, &[], &[]).map_err(|e| format!("Cannot copy crate for check: {}", e))?;
+
+    // Generated crates hold copies of resources/*.rs; replace them with the current files
+    if !mirrors_app {
+        let mut pending = vec![dir.clone()];
+        while let Some(current) = pending.pop() {
+            for entry in std::fs::read_dir(&current).map_err(|e| format!("Cannot read check dir: {}", e))?.flatten() {
+                let path = entry.path();
+                if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                    pending.push(path);
+                } else if path.extension().is_some_and(|ext| ext == "rs") {
+                    let current_source = app_path.join("resources").join(entry.file_name());
+                    if current_source.is_file() {
+                        // The copy may be a symlink into the app; replace the link, not its target
+                        let _ = std::fs::remove_file(&path);
+                        std::fs::copy(&current_source, &path).map_err(|e| format!("Cannot copy source: {}", e))?;
+                    }
+                }
+            }
+        }
+    }
+    Ok(CheckCrate { dir, mirrors_app })
+}
+
+/// Map a diagnostic span's file back to a path relative to the app
+fn diagnostic_file(file_name: &str, check: &CheckCrate, app_path: &Path) -> String {
+    if check.mirrors_app && Path::new(file_name).is_relative() {
+        return file_name.to_string();
+    }
+    let path = check.dir.join(file_name);
+    let canonical = path.canonicalize().unwrap_or(path);
+    if let Ok(app_root) = app_path.canonicalize() {
+        if let Ok(rel) = canonical.strip_prefix(&app_root) {
+            return rel.to_string_lossy().to_string();
+        }
+    }
+    // Generated crates hold copies of resources/*.rs; match them by file name
+    if let Some(name) = canonical.file_name() {
+        if app_path.join("resources").join(name).is_file() {
+            return format!("resources/{}", name.to_string_lossy());
+        }
+    }
+    file_name.to_string()
+}
+
+/// Structured diagnostic from one `compiler-message` line of cargo's JSON output
+fn parse_diagnostic(message: &serde_json::Value, check: &CheckCrate, app_path: &Path) -> Option<serde_json::Value> {
+    let severity = message["level"].as_str()?;
+    let text = message["message"].as_str()?;
+    let spans = message["spans"].as_array()?;
+    let primary = spans.iter().find(|s| s["is_primary"].as_bool() == Some(true));
+    if primary.is_none() && (severity == "failure-note" || text.starts_with("aborting due to")) {
+        return None;
+    }
+
+    // Suggested fixes come from child notes carrying a replacement
+    let suggestions: Vec<serde_json::Value> = message["children"].as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|child| {
+            let label = child["message"].as_str().unwrap_or("").to_string();
+            child["spans"].as_array().into_iter().flatten().filter_map(move |span| {
+                let replacement = span["suggested_replacement"].as_str()?;
+                Some(json!({
+                    "message": label,
+                    "line": span["line_start"],
+                    "column": span["column_start"],
+                    "end_line": span["line_end"],
+                    "end_column": span["column_end"],
+                    "replacement": replacement,
+                }))
+            })
+        })
+        .collect();
+
+    Some(json!({
+        "file": primary.and_then(|s| s["file_name"].as_str()).map(|f| diagnostic_file(f, check, app_path)),
+        "line": primary.map(|s| s["line_start"].clone()),
+        "column": primary.map(|s| s["column_start"].clone()),
+        "severity": severity,
+        "code": message["code"]["code"],
+        "message": text,
+        "label": primary.and_then(|s| s["label"].as_str()),
+        "suggestions": suggestions,
+        "rendered": message["rendered"],
+    }))
+}
+
+/// Run `cargo check` for an app's resources and collect its diagnostics.
+/// Runs for the same app are serialised through a lock file in the cache dir.
+/// Blocks for up to `timeout`; handlers call it through `compile_check`.
+fn run_compile_check(app_id: &str, app_path: &Path, timeout: std::time::Duration) -> std::result::Result<serde_json::Value, RunError> {
+    let started = std::time::Instant::now();
+    let cache_dir = get_cache_directory();
+    std::fs::create_dir_all(&cache_dir).map_err(|e| RunError::Failed(format!("Cannot create cache dir: {}", e)))?;
+    let lock_file = std::fs::File::create(cache_dir.join(format!(".check-{}.lock", app_id)))
+        .map_err(|e| RunError::Failed(format!("Cannot create check lock: {}", e)))?;
+    loop {
+        match lock_file.try_lock() {
+            Ok(()) => break,
+            Err(std::fs::TryLockError::WouldBlock) if started.elapsed() < timeout => {
+                std::thread::sleep(std::time::Duration::from_millis(100));
+            }
+            Err(std::fs::TryLockError::WouldBlock) => {
+                return Err(RunError::Busy(format!("Another check of '{}' is still running", app_id)));
+            }
+            Err(std::fs::TryLockError::Error(e)) => return Err(RunError::Failed(format!("Cannot lock check: {}", e))),
+        }
+    }
+    let check = prepare_check_crate(app_id, app_path).map_err(RunError::Failed)?;
+
+    let output_path = cache_dir.join(format!(".check-{}.out", app_id));
+    let output_file = std::fs::File::create(&output_path)
+        .map_err(|e| RunError::Failed(format!("Cannot create check output: {}", e)))?;
+    let mut child = {
+        use std::os::unix::process::CommandExt;
+        std::process::Command::new("cargo")
+            .args(["check", "--message-format=json", "--quiet"])
+            .current_dir(&check.dir)
+            .env("CARGO_TARGET_DIR", cache_dir.join(format!(".check-target-{}", app_id)))
+            .env("CARGO_TERM_COLOR", "never")
+            .stdin(std::process::Stdio::null())
+            .stdout(output_file)
+            .stderr(std::process::Stdio::null())
+            .process_group(0)
+            .spawn()
+            .map_err(|e| RunError::Failed(format!("Failed to run cargo: {}", e)))?
+    };
+
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() > timeout => {
+                let _ = std::process::Command::new("kill")
+                    .args(["-KILL", "--", &format!("-{}", child.id())])
+                    .status();
+                let _ = child.kill();
+                let _ = child.wait();
+                let _ = std::fs::remove_file(&output_path);
+                return Err(RunError::Timeout(format!("Check timed out after {}s", timeout.as_secs())));
+            }
+            Ok(None) => std::thread::sleep(std::time::Duration::from_millis(100)),
+            Err(e) => return Err(RunError::Failed(format!("Failed to wait for cargo: {}", e))),
+        }
+    };
+
+    let output = std::fs::read_to_string(&output_path).unwrap_or_default();
+    let _ = std::fs::remove_file(&output_path);
+    let diagnostics: Vec<serde_json::Value> = output.lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|msg| msg["reason"] == "compiler-message")
+        .filter_map(|msg| parse_diagnostic(&msg["message"], &check, app_path))
+        .collect();
+    let count = |severity: &str| diagnostics.iter().filter(|d| d["severity"] == severity).count();
+
+    Ok(json!({
+        "app_id": app_id,
+        "success": status.success(),
+        "errors": count("error"),
+        "warnings": count("warning"),
+        "duration_ms": started.elapsed().as_millis() as u64,
+        "diagnostics": diagnostics,
+    }))
+}
+
+/// Compile-check an app on the blocking thread pool, with the configured timeout
+pub(crate) async fn compile_check(app_id: &str) -> std::result::Result<serde_json::Value, RunError> {
+    let app_id = app_id.to_string();
+    let app_path = get_apps_directory().join(&app_id);
+    let timeout = std::time::Duration::from_secs(check_timeout_secs());
+    tokio::task::spawn_blocking(move || run_compile_check(&app_id, &app_path, timeout))
+        .await
+        .map_err(|e| RunError::Failed(format!("Check failed: {}", e)))?
+}
+
+/// Whether `.rs` saves in the file editor are checked by default (`admin.check.on_save`)
+pub(crate) fn check_on_save() -> bool {
+    admin_settings()
+        .pointer("/check/on_save")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+// ── Resource scaffolding ──
+
+/// A field of a schema table
+struct FieldDef {
+    name: String,
+    /// GraphQL type without `!` / list brackets
+    base_type: String,
+    required: bool,
+    list: bool,
+}
+
+/// A `type X @table(...)` definition
+struct TableDef {
+    name: String,
+    database: String,
+    fields: Vec<FieldDef>,
+}
+
+/// Parse one field type such as `String!`, `[Int]` or `[String!]!`
+fn parse_field_type(type_part: &str) -> (String, bool, bool) {
+    let ty = type_part.split('@').next().unwrap_or(type_part).trim();
+    let required = ty.ends_with('!');
+    let inner = ty.trim_end_matches('!');
+    let list = inner.starts_with('[');
+    let base = inner.trim_start_matches('[').trim_end_matches(']').trim_end_matches('!').trim();
+    (base.to_string(), required, list)
+}
+
+/// Find a table definition by name in schema content
+fn find_table(content: &str, table: &str) -> Option<TableDef> {
+    let mut current: Option<TableDef> = None;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if let Some(def) = current.as_mut() {
+            if trimmed.starts_with('}') {
+                return current;
+            }
+            if trimmed.starts_with('#') || !trimmed.contains(':') {
+                continue;
+            }
+            let (name, type_part) = trimmed.split_once(':').unwrap_or_default();
+            let (base_type, required, list) = parse_field_type(type_part);
+            if !name.trim().is_empty() {
+                def.fields.push(FieldDef { name: name.trim().to_string(), base_type, required, list });
+            }
+            continue;
+        }
+        if let Some((name, database)) = schema_tables(trimmed).into_iter().next() {
+            if name == table {
+                current = Some(TableDef { name, database, fields: Vec::new() });
+            }
+        }
+    }
+    current
+}
+
+/// `OrderItem` -> `order_item`
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else if c == '-' {
+            out.push('_');
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Name of the JSON check for a GraphQL scalar, or None for types that aren't checked
+fn json_type_check(base_type: &str) -> Option<&'static str> {
+    match base_type {
+        "ID" | "String" => Some("string"),
+        "Int" => Some("integer"),
+        "Float" => Some("number"),
+        "Boolean" => Some("boolean"),
+        _ => None,
+    }
+}
+
+/// Render a resource skeleton for a table, in the same layout as this app's resources
+fn render_resource_scaffold(table: &TableDef, route_prefix: &str) -> String {
+    let resource_name = snake_case(&table.name);
+    let url = format!("{}/{}", route_prefix.trim_end_matches('/'), resource_name);
+    let mut code = String::new();
+    let mut line = |text: &str| {
+        code.push_str(text);
+        code.push('\n');
+    };
+
+    line(&format!("//! {} Resource", table.name));
+    line("//!");
+    line(&format!("//! REST API for the `{}` table (database `{}`).", table.name, table.database));
+    line("//!");
+    let rows = [
+        ("GET", url.clone(), "List records"),
+        ("GET", format!("{}/{{id}}", url), "Get one record"),
+        ("POST", url.clone(), "Create record"),
+        ("PUT", format!("{}/{{id}}", url), "Replace record"),
+        ("DELETE", format!("{}/{{id}}", url), "Delete record"),
+    ];
+    let width = rows.iter().map(|(_, path, _)| path.len()).max().unwrap_or(0);
+    line(&format!("//! | Method | {:width$} | Description    |", "Path"));
+    line(&format!("//! |--------|-{}-|----------------|", "-".repeat(width)));
+    for (method, path, description) in &rows {
+        line(&format!("//! | {:6} | {:width$} | {:14} |", method, path, description));
+    }
+    line("");
+    line("use yeti_core::prelude::*;");
+    line("");
+    line(&format!("pub type {} = {}Resource;", table.name, table.name));
+    line("");
+    line("#[derive(Default)]");
+    line(&format!("pub struct {}Resource;", table.name));
+    line("");
+    line("/// Check that a field, if present, has the expected JSON type");
+    line("fn check_field(record: &serde_json::Map<String, serde_json::Value>, name: &str, expected: &str, required: bool, list: bool) -> Result<()> {");
+    line("    let Some(value) = record.get(name).filter(|v| !v.is_null()) else {");
+    line("        if required {");
+    line("            return Err(YetiError::Validation(format!(\"'{}' is required\", name)));");
+    line("        }");
+    line("        return Ok(());");
+    line("    };");
+    line("    let matches = |v: &serde_json::Value| match expected {");
+    line("        \"string\" => v.is_string(),");
+    line("        \"integer\" => v.is_i64() || v.is_u64(),");
+    line("        \"number\" => v.is_number(),");
+    line("        \"boolean\" => v.is_boolean(),");
+    line("        _ => true,");
+    line("    };");
+    line("    let valid = if list {");
+    line("        value.as_array().is_some_and(|items| items.iter().all(matches))");
+    line("    } else {");
+    line("        matches(value)");
+    line("    };");
+    line("    if !valid {");
+    line("        let shape = if list { format!(\"a list of {}\", expected) } else { expected.to_string() };");
+    line("        return Err(YetiError::Validation(format!(\"'{}' must be {}\", name, shape)));");
+    line("    }");
+    line("    Ok(())");
+    line("}");
+    line("");
+    line(&format!("/// Validate a {} record against the schema (`!` fields are required)", table.name));
+    line("fn validate_record(record: &serde_json::Value) -> Result<()> {");
+    line("    let record = record.as_object()");
+    line("        .ok_or_else(|| YetiError::Validation(\"Body must be a JSON object\".to_string()))?;");
+    for field in &table.fields {
+        let expected = json_type_check(&field.base_type).unwrap_or("any");
+        line(&format!(
+            "    check_field(record, \"{}\", \"{}\", {}, {})?;",
+            field.name, expected, field.required, field.list
+        ));
+    }
+    line("    Ok(())");
+    line("}");
+    line("");
+    line(&format!("impl Resource for {}Resource {{", table.name));
+    line("    fn name(&self) -> &str {");
+    line(&format!("        \"{}\"", resource_name));
+    line("    }");
+    line("");
+    line("    get!(_request, ctx, {");
+    line(&format!("        let table = ctx.get_table(\"{}\")?;", table.name));
+    line("");
+    line("        if let Some(id) = ctx.path_id() {");
+    line("            return match table.get_by_id(id).await? {");
+    line("                Some(record) => reply().json(record),");
+    line(&format!("                None => not_found(&format!(\"{} '{{}}' not found\", id)),", table.name));
+    line("            };");
+    line("        }");
+    line("");
+    line("        let records = table.scan_all().await?;");
+    line("        reply().json(json!(records))");
+    line("    });");
+    line("");
+    line("    post!(request, ctx, {");
+    line("        let body = request.json_value()?;");
+    line("        validate_record(&body)?;");
+    line("        let id = body.require_str(\"id\")?;");
+    line("");
+    line(&format!("        let table = ctx.get_table(\"{}\")?;", table.name));
+    line("        if table.get_by_id(&id).await?.is_some() {");
+    line(&format!("            return bad_request(&format!(\"{} '{{}}' already exists\", id));", table.name));
+    line("        }");
+    line("        table.put(&id, body.clone()).await?;");
+    line("");
+    line("        reply().code(201).json(body)");
+    line("    });");
+    line("");
+    line("    put!(request, ctx, {");
+    line("        let id = ctx.require_id()?.to_string();");
+    line("        let mut body = request.json_value()?;");
+    line("        body[\"id\"] = json!(id);");
+    line("        validate_record(&body)?;");
+    line("");
+    line(&format!("        let table = ctx.get_table(\"{}\")?;", table.name));
+    line("        if table.get_by_id(&id).await?.is_none() {");
+    line(&format!("            return not_found(&format!(\"{} '{{}}' not found\", id));", table.name));
+    line("        }");
+    line("        table.put(&id, body.clone()).await?;");
+    line("");
+    line("        reply().json(body)");
+    line("    });");
+    line("");
+    line("    delete!(_request, ctx, {");
+    line("        let id = ctx.require_id()?.to_string();");
+    line(&format!("        let table = ctx.get_table(\"{}\")?;", table.name));
+    line("        if table.get_by_id(&id).await?.is_none() {");
+    line(&format!("            return not_found(&format!(\"{} '{{}}' not found\", id));", table.name));
+    line("        }");
+    line("        table.delete(&id).await?;");
+    line("");
+    line("        reply().json(json!({\"deleted\": true, \"id\": id}))");
+    line("    });");
+    line("}");
+    line("");
+    line(&format!("register_resource!({}Resource);", table.name));
+    code
+}
+
+// ── Bulk operations ──
+
+const BULK_ACTIONS: &[&str] = &["enable", "disable", "delete", "pull"];
+
+/// Check a bulk action can run on an app; returns what it would do
+fn bulk_plan(action: &str, app_id: &str, confirmed: &[String]) -> std::result::Result<String, String> {
+    validate_identifier(app_id, "app_id").map_err(|e| e.to_string())?;
+    let app_path = get_apps_directory().join(app_id);
+    if !app_path.is_dir() {
+        return Err(format!("Application '{}' not found", app_id));
+    }
+
+    match action {
+        "enable" | "disable" => {
+            let config = read_app_config(&app_path)
+                .ok_or_else(|| "config.yaml is missing or invalid".to_string())?;
+            let want = action == "enable";
+            let current = config.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false);
+            Ok(if current == want {
+                format!("already {}d", action)
+            } else {
+                format!("set enabled: {}", want)
+            })
+        }
+        "delete" => {
+            let confirm = confirmed.iter().any(|c| c == app_id).then_some(app_id);
+            if let Some(rejection) = protection_rejection(app_id, "deleted", confirm) {
+                return Err(rejection["error"].as_str().unwrap_or("protected").to_string());
+            }
+            Ok("move to trash".to_string())
+        }
+        "pull" => {
+            if !app_path.join(".git").is_dir() {
+                return Err(format!("Application '{}' is not a git repository", app_id));
+            }
+            Ok(format!("git pull ({})", git_remote_url(&app_path).unwrap_or_else(|| "no origin".to_string())))
+        }
+        _ => Err(format!("Unknown action '{}'", action)),
+    }
+}
+
+/// `git pull --ff-only` with an optional deploy key and a timeout.
+/// git runs in its own process group so a timeout also kills the ssh it spawned.
+fn bulk_git_pull(app_path: &Path, key: Option<&str>) -> std::result::Result<String, String> {
+    use std::io::Read;
+    use std::os::unix::process::CommandExt;
+
+    let mut cmd = super::repos::git_command(key)?;
+    cmd.arg("-C").arg(app_path).args(["pull", "--ff-only"]);
+    let mut child = cmd
+        .process_group(0)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    // Drain both pipes on their own threads so a chatty git never blocks on a full pipe
+    fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> std::thread::JoinHandle<String> {
+        std::thread::spawn(move || {
+            let mut text = String::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_string(&mut text);
+            }
+            text
+        })
+    }
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let started = std::time::Instant::now();
+    let timeout = std::time::Duration::from_secs(BULK_PULL_TIMEOUT_SECS);
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Ok(status),
+            Ok(None) if started.elapsed() > timeout => {
+                let _ = std::process::Command::new("kill")
+                    .args(["-KILL", "--", &format!("-{}", child.id())])
+                    .status();
+                let _ = child.kill();
+                let _ = child.wait();
+                break Err(format!("git pull timed out after {}s", BULK_PULL_TIMEOUT_SECS));
+            }
+            Ok(None) => std::thread::sleep(std::time::Duration::from_millis(100)),
+            Err(e) => break Err(format!("Failed to wait for git: {}", e)),
+        }
+    };
+    let output = format!(
+        "{}{}",
+        stdout.join().unwrap_or_default(),
+        stderr.join().unwrap_or_default()
+    );
+
+    match status? {
+        status if status.success() => Ok(output.trim().to_string()),
+        _ => Err(format!("git pull failed: {}", output.trim())),
+    }
+}
+
+/// Run one bulk action. Config changes carry `previous_content`/`content` so the
+/// caller can record them as revisions.
+fn bulk_execute(action: &str, app_id: &str, key: Option<&str>) -> std::result::Result<serde_json::Value, String> {
+    let apps_path = get_apps_directory();
+    let app_path = apps_path.join(app_id);
+    match action {
+        "enable" | "disable" => {
+            let want = action == "enable";
+            let current = read_app_config(&app_path)
+                .and_then(|c| c.get("enabled").and_then(|v| v.as_bool()))
+                .unwrap_or(false);
+            if current == want {
+                return Ok(json!({"changed": false}));
+            }
+            let (previous, content) = update_config_values(&app_path.join("config.yaml"), &[("enabled", json!(want))])
+                .map_err(|e| e.to_string())?;
+            Ok(json!({"changed": true, "previous_content": previous, "content": content}))
+        }
+        "delete" => {
+            let trash_entry = move_to_trash(&apps_path, app_id)
+                .map_err(|e| format!("Failed to move app to trash: {}", e))?;
+            let cache_path = get_cache_directory().join(app_id);
+            if cache_path.is_dir() {
+                let _ = std::fs::remove_dir_all(&cache_path);
+            }
+            Ok(json!({"changed": true, "trash_entry": trash_entry}))
+        }
+        "pull" => {
+            let output = bulk_git_pull(&app_path, key)?;
+            Ok(json!({"changed": !output.contains("Already up to date"), "output": output}))
+        }
+        _ => Err(format!("Unknown action '{}'", action)),
+    }
+}
+
+/// Run `bulk_execute` over the apps with at most `concurrency` at a time, keeping input order.
+/// Blocks until every app is done, so the handler runs it with `spawn_blocking`.
+fn run_bulk(action: &str, app_ids: &[String], key: Option<&str>, concurrency: usize) -> Vec<std::result::Result<serde_json::Value, String>> {
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<std::result::Result<serde_json::Value, String>>>> =
+        Mutex::new(app_ids.iter().map(|_| None).collect());
+
+    std::thread::scope(|scope| {
+        for _ in 0..concurrency.min(app_ids.len()) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let Some(app_id) = app_ids.get(i) else { break };
+                let result = bulk_execute(action, app_id, key);
+                results.lock().unwrap_or_else(|e| e.into_inner())[i] = Some(result);
+            });
+        }
+    });
+
+    results.into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_iter()
+        .map(|r| r.unwrap_or_else(|| Err("not run".to_string())))
+        .collect()
+}
+
+// ── Route and database conflicts ──
+
+/// Routing and storage claims of one app
+struct AppClaims {
+    app_id: String,
+    /// Effective route prefix (`route_prefix`, or `/<app_id>` when unset)
+    route_prefix: String,
+    /// Full path static files are served under, if the app serves any
+    static_route: Option<String>,
+    /// (database, table) pairs; tables without a database use the app's own
+    tables: Vec<(String, String)>,
+}
+
+/// Normalise a route to a leading slash and no trailing slash (`/` stays `/`)
+fn normalize_route(route: &str) -> String {
+    let trimmed = route.trim().trim_matches('/');
+    if trimmed.is_empty() { "/".to_string() } else { format!("/{}", trimmed) }
+}
+
+/// Join a route onto a prefix
+fn join_route(prefix: &str, route: &str) -> String {
+    normalize_route(&format!("{}/{}", prefix, route))
+}
+
+/// Whether `route` is `base` or lies underneath it
+fn route_within(route: &str, base: &str) -> bool {
+    base == "/" || route == base || route.starts_with(&format!("{}/", base))
+}
+
+/// `(table, database)` for each `type X @table(...)` line, database empty when not given
+fn schema_tables(content: &str) -> Vec<(String, String)> {
+    let mut tables = Vec::new();
+    for line in content.lines() {
+        let trimmed = line.trim();
+        let Some(after_type) = trimmed.strip_prefix("type ") else { continue };
+        let Some(table_pos) = trimmed.find("@table") else { continue };
+        let name = after_type.split(|c: char| c.is_whitespace() || c == '@' || c == '{')
+            .next()
+            .unwrap_or("")
+            .to_string();
+        let directive = &trimmed[table_pos..];
+        let database = directive.find("database:")
+            .map(|start| directive[start + "database:".len()..].trim_start())
+            .and_then(|rest| rest.strip_prefix('"'))
+            .and_then(|rest| rest.split('"').next())
+            .unwrap_or("")
+            .to_string();
+        if !name.is_empty() {
+            tables.push((name, database));
+        }
+    }
+    tables
+}
+
+/// Claims of an app from its config and the schema files at `app_path`
+fn app_claims(app_id: &str, app_path: &Path, config: &serde_json::Value) -> AppClaims {
+    let route_prefix = normalize_route(
+        config.get("route_prefix").and_then(|v| v.as_str()).unwrap_or(app_id)
+    );
+    let static_route = config.get("static_files").map(|sf| {
+        let route = sf.get("route").and_then(|v| v.as_str()).unwrap_or("/");
+        join_route(&route_prefix, route)
+    });
+
+    let mut tables = Vec::new();
+    for schema_path in schema_files(app_path, Some(config)) {
+        if let Ok(content) = std::fs::read_to_string(&schema_path) {
+            for (table, database) in schema_tables(&content) {
+                let database = if database.is_empty() { app_id.to_string() } else { database };
+                tables.push((database, table));
+            }
+        }
+    }
+    tables.sort();
+    tables.dedup();
+
+    AppClaims { app_id: app_id.to_string(), route_prefix, static_route, tables }
+}
+
+/// Claims of every installed app, skipping `exclude`
+fn installed_claims(exclude: &[&str]) -> Vec<AppClaims> {
+    let apps_path = get_apps_directory();
+    let mut claims = Vec::new();
+    let Ok(entries) = std::fs::read_dir(&apps_path) else { return claims };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let id = entry.file_name().to_string_lossy().to_string();
+        if id.starts_with('.') || !path.is_dir() || exclude.contains(&id.as_str()) {
+            continue;
+        }
+        if let Some(config) = read_app_config(&path) {
+            claims.push(app_claims(&id, &path, &config));
+        }
+    }
+    claims.sort_by(|a, b| a.app_id.cmp(&b.app_id));
+    claims
+}
+
+/// Duplicate route prefixes, static routes shadowing other apps, and shared
+/// database/table pairs. Each conflict carries a stable `key` for comparison.
+fn detect_conflicts(claims: &[AppClaims]) -> Vec<serde_json::Value> {
+    let mut conflicts = Vec::new();
+
+    let mut prefixes: std::collections::BTreeMap<&str, Vec<&str>> = std::collections::BTreeMap::new();
+    let mut tables: std::collections::BTreeMap<(&str, &str), Vec<&str>> = std::collections::BTreeMap::new();
+    for app in claims {
+        prefixes.entry(app.route_prefix.as_str()).or_default().push(&app.app_id);
+        for (database, table) in &app.tables {
+            tables.entry((database.as_str(), table.as_str())).or_default().push(&app.app_id);
+        }
+    }
+
+    for (prefix, apps) in &prefixes {
+        if apps.len() > 1 {
+            conflicts.push(json!({
+                "type": "duplicate_route_prefix",
+                "key": format!("route:{}", prefix),
+                "route_prefix": prefix,
+                "apps": apps,
+            }));
+        }
+    }
+
+    for app in claims {
+        let Some(static_route) = &app.static_route else { continue };
+        for other in claims {
+            if other.app_id == app.app_id || other.route_prefix == app.route_prefix {
+                continue;
+            }
+            if route_within(&other.route_prefix, static_route) {
+                conflicts.push(json!({
+                    "type": "shadowed_static_route",
+                    "key": format!("static:{}>{}", static_route, other.route_prefix),
+                    "app_id": app.app_id,
+                    "static_route": static_route,
+                    "shadows": other.app_id,
+                    "route_prefix": other.route_prefix,
+                    "apps": [app.app_id, other.app_id],
+                }));
+            }
+        }
+    }
+
+    for ((database, table), apps) in &tables {
+        if apps.len() > 1 {
+            conflicts.push(json!({
+                "type": "shared_table",
+                "key": format!("table:{}:{}", database, table),
+                "database": database,
+                "table": table,
+                "apps": apps,
+            }));
+        }
+    }
+
+    conflicts
+}
+
+/// Identity of a conflict: its subject plus the apps involved
+fn conflict_identity(conflict: &serde_json::Value) -> String {
+    let mut apps: Vec<&str> = conflict["apps"].as_array()
+        .map(|a| a.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+    apps.sort_unstable();
+    format!("{}|{}", conflict["key"].as_str().unwrap_or(""), apps.join(","))
+}
+
+/// 409 body if installing `candidate` (in place of the apps in `replacing`)
+/// would introduce conflicts that don't exist today. Conflicts the replaced apps
+/// already had are carried over under the candidate's id.
+fn conflict_rejection(candidate: AppClaims, replacing: &[&str]) -> Option<serde_json::Value> {
+    let candidate_id = candidate.app_id.clone();
+
+    let mut current = installed_claims(&[]);
+    for app in &mut current {
+        if replacing.contains(&app.app_id.as_str()) {
+            app.app_id = candidate_id.clone();
+        }
+    }
+    let before: std::collections::HashSet<String> = detect_conflicts(&current)
+        .iter()
+        .map(conflict_identity)
+        .collect();
+
+    let mut exclude: Vec<&str> = replacing.to_vec();
+    exclude.push(&candidate_id);
+    let mut after = installed_claims(&exclude);
+    after.push(candidate);
+
+    let introduced: Vec<serde_json::Value> = detect_conflicts(&after)
+        .into_iter()
+        .filter(|c| {
+            c["apps"].as_array().is_some_and(|apps| apps.iter().any(|a| a == candidate_id.as_str()))
+                && !before.contains(&conflict_identity(c))
+        })
+        .collect();
+
+    if introduced.is_empty() {
+        return None;
+    }
+    Some(json!({
+        "error": format!("'{}' would conflict with existing applications", candidate_id),
+        "conflicts": introduced,
+    }))
+}
+
+// ── Route map ──
+
+/// Names of the `@export` tables in a schema file
+fn schema_exports(content: &str) -> Vec<String> {
+    content.lines()
+        .map(str::trim)
+        .filter(|line| line.contains("@table") && line.contains("@export"))
+        .filter_map(|line| line.strip_prefix("type "))
+        .filter_map(|rest| rest.split(|c: char| c.is_whitespace() || c == '@' || c == '{').next())
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Resource name returned by `fn name(&self)` in a resource source file: the
+/// first such fn whose body is a plain string literal
+fn resource_name(content: &str) -> Option<String> {
+    content.match_indices("fn name(&self)").find_map(|(pos, _)| {
+        let after_fn = &content[pos..];
+        let body = after_fn[after_fn.find('{')? + 1..].trim_start().strip_prefix('"')?;
+        let name = &body[..body.find('"')?];
+        body[name.len() + 1..].trim_start().starts_with('}').then(|| name.to_string())
+    })
+}
+
+/// Every route an app serves: its prefix, static files, exported tables,
+/// resources and the extensions it enables
+fn app_routes(app_id: &str, app_path: &Path, config: &serde_json::Value) -> Vec<serde_json::Value> {
+    let claims = app_claims(app_id, app_path, config);
+    let enabled = config.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false);
+    let entry = |route: &str, kind: &str, name: &str, source: &str| json!({
+        "route": route,
+        "kind": kind,
+        "name": name,
+        "app_id": app_id,
+        "source": source,
+        "enabled": enabled,
+    });
+
+    let mut routes = vec![entry(&claims.route_prefix, "app", app_id, "config.yaml")];
+
+    if let Some(static_route) = &claims.static_route {
+        let dir = config["static_files"].get("path").and_then(|v| v.as_str()).unwrap_or("");
+        routes.push(entry(static_route, "static", dir, "config.yaml"));
+    }
+
+    // Same URL as `rest_url` in SchemasResource
+    for schema_path in schema_files(app_path, Some(config)) {
+        let Ok(content) = std::fs::read_to_string(&schema_path) else { continue };
+        let source = schema_path.strip_prefix(app_path).unwrap_or(&schema_path).to_string_lossy().to_string();
+        for table in schema_exports(&content) {
+            routes.push(entry(&format!("/{}/{}", app_id, table), "table", &table, &source));
+        }
+    }
+
+    if let Ok(entries) = std::fs::read_dir(app_path.join("resources")) {
+        for file in entries.flatten() {
+            let path = file.path();
+            if path.extension().is_none_or(|ext| ext != "rs") {
+                continue;
+            }
+            let Some(name) = std::fs::read_to_string(&path).ok().and_then(|c| resource_name(&c)) else { continue };
+            let source = format!("resources/{}", file.file_name().to_string_lossy());
+            routes.push(entry(&join_route(&claims.route_prefix, &name), "resource", &name, &source));
+        }
+    }
+
+    // Extensions are listed as a name or a single-key mapping of name -> settings
+    let extensions = config.get("extensions").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+    for extension in &extensions {
+        let name = match extension {
+            serde_json::Value::String(name) => name.clone(),
+            serde_json::Value::Object(map) => match map.keys().next() {
+                Some(name) => name.clone(),
+                None => continue,
+            },
+            _ => continue,
+        };
+        let route = read_app_config(&get_apps_directory().join(&name))
+            .and_then(|c| c.get("route_prefix").and_then(|v| v.as_str()).map(normalize_route))
+            .unwrap_or_else(|| normalize_route(&name));
+        routes.push(entry(&route, "extension", &name, "config.yaml"));
+    }
+
+    routes
+}
+
+/// One path segment of the route tree
+#[derive(Default)]
+struct RouteNode {
+    routes: Vec<serde_json::Value>,
+    children: std::collections::BTreeMap<String, RouteNode>,
+}
+
+impl RouteNode {
+    fn insert(&mut self, route: serde_json::Value) {
+        let path = route["route"].as_str().unwrap_or("/").to_string();
+        let mut node = self;
+        for segment in path.split('/').filter(|s| !s.is_empty()) {
+            node = node.children.entry(segment.to_string()).or_default();
+        }
+        node.routes.push(route);
+    }
+
+    fn to_json(&self, path: &str) -> serde_json::Value {
+        let mut routes = self.routes.clone();
+        routes.sort_by(|a, b| {
+            (a["app_id"].as_str(), a["kind"].as_str(), a["name"].as_str())
+                .cmp(&(b["app_id"].as_str(), b["kind"].as_str(), b["name"].as_str()))
+        });
+        let children: Vec<serde_json::Value> = self.children.iter()
+            .map(|(segment, child)| child.to_json(&join_route(path, segment)))
+            .collect();
+        json!({
+            "path": path,
+            "routes": routes,
+            "children": children,
+        })
+    }
+}
+
+// ── Config revisions ──
+//
+// Revisions of an app are numbered 1, 2, 3... and stored as `{app_id}-{n}`.
+// The app's ConfigRevisionHead record holds the latest number; it is advanced
+// under `REVISION_LOCK`, so concurrent writes never reuse a number, and history
+// is read by id instead of scanning the table.
+
+/// Serialises revision number allocation
+static REVISION_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Latest revision number of an app. Apps recorded before ConfigRevisionHead
+/// existed have no head; their (gapless) numbers are counted once instead.
+/// Evaluates to `Result<u64>`.
+macro_rules! latest_revision {
+    ($heads:ident, $revisions:ident, $app_id:expr) => {
+        async {
+            let app_id: &str = $app_id;
+            match $heads.get_by_id(app_id).await? {
+                Some(head) => Ok::<u64, YetiError>(head["latest"].as_u64().unwrap_or(0)),
+                None => {
+                    let mut latest = 0;
+                    while $revisions.get_by_id(&format!("{}-{}", app_id, latest + 1)).await?.is_some() {
+                        latest += 1;
+                    }
+                    Ok(latest)
+                }
+            }
+        }
+    };
+}
+
+/// Record a config.yaml write as the app's next revision; `$record` builds the
+/// record from the allocated number. Evaluates to `Result<u64>`.
+macro_rules! record_revision {
+    ($ctx:ident, $app_id:expr, $record:expr) => {
+        async {
+            let app_id: &str = $app_id;
+            let heads = $ctx.get_table("ConfigRevisionHead")?;
+            let revisions = $ctx.get_table("ConfigRevision")?;
+            let next = {
+                let _guard = REVISION_LOCK.lock().await;
+                let next = latest_revision!(heads, revisions, app_id).await? + 1;
+                heads.put(app_id, json!({"id": app_id, "latest": next})).await?;
+                next
+            };
+            let build = $record;
+            revisions.put(&format!("{}-{}", app_id, next), build(next)).await?;
+            Ok::<u64, YetiError>(next)
+        }
+    };
+}
+
+/// All revisions of an app, newest first. Evaluates to `Result<Vec<Value>>`.
+macro_rules! revision_history {
+    ($ctx:ident, $app_id:expr) => {
+        async {
+            let app_id: &str = $app_id;
+            let heads = $ctx.get_table("ConfigRevisionHead")?;
+            let revisions = $ctx.get_table("ConfigRevision")?;
+            let latest = latest_revision!(heads, revisions, app_id).await?;
+            let mut history = Vec::new();
+            for number in (1..=latest).rev() {
+                if let Some(record) = revisions.get_by_id(&format!("{}-{}", app_id, number)).await? {
+                    history.push(record);
+                }
+            }
+            Ok::<Vec<serde_json::Value>, YetiError>(history)
+        }
+    };
+}
+
+/// Move an app's revision history (and head) to a new app_id, replacing any
+/// history left under that id. Records are copied before the old ones are removed.
+/// Evaluates to `Result<u64>`, the number of revisions moved.
+macro_rules! migrate_revisions {
+    ($ctx:ident, $old_id:expr, $new_id:expr) => {
+        async {
+            let (old_id, new_id): (&str, &str) = ($old_id, $new_id);
+            let heads = $ctx.get_table("ConfigRevisionHead")?;
+            let revisions = $ctx.get_table("ConfigRevision")?;
+            let _guard = REVISION_LOCK.lock().await;
+            let stale = latest_revision!(heads, revisions, new_id).await?;
+            for number in 1..=stale {
+                revisions.delete(&format!("{}-{}", new_id, number)).await?;
+            }
+            let latest = latest_revision!(heads, revisions, old_id).await?;
+            let mut moved = Vec::new();
+            for number in 1..=latest {
+                if let Some(mut record) = revisions.get_by_id(&format!("{}-{}", old_id, number)).await? {
+                    let id = format!("{}-{}", new_id, number);
+                    record["id"] = json!(id);
+                    record["appId"] = json!(new_id);
+                    revisions.put(&id, record).await?;
+                    moved.push(number);
+                }
+            }
+            heads.put(new_id, json!({"id": new_id, "latest": latest})).await?;
+            for number in &moved {
+                revisions.delete(&format!("{}-{}", old_id, number)).await?;
+            }
+            heads.delete(old_id).await?;
+            Ok::<u64, YetiError>(moved.len() as u64)
+        }
+    };
+}
+
+/// One-line description of a config diff, e.g. "changed /enabled; added /route_prefix"
+fn diff_summary(diff: &[serde_json::Value]) -> String {
+    if diff.is_empty() {
+        return "no changes".to_string();
+    }
+    let mut parts: Vec<String> = diff.iter()
+        .take(5)
+        .map(|c| format!("{} {}", c["op"].as_str().unwrap_or(""), c["path"].as_str().unwrap_or("")))
+        .collect();
+    if diff.len() > 5 {
+        parts.push(format!("and {} more", diff.len() - 5));
+    }
+    parts.join("; ")
+}
+
+/// ConfigRevision record for a config.yaml write
+fn revision_record(
+    app_id: &str,
+    revision: u64,
+    user: &str,
+    action: &str,
+    previous_content: &str,
+    content: &str,
+    diff: &[serde_json::Value],
+) -> serde_json::Value {
+    json!({
+        "id": format!("{}-{}", app_id, revision),
+        "appId": app_id,
+        "revision": revision,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "user": user,
+        "action": action,
+        "previousContent": previous_content,
+        "content": content,
+        "diff": serde_json::to_string(diff).unwrap_or_default(),
+        "summary": diff_summary(diff),
+    })
+}
+
+/// Revision listing entry (content omitted, diff decoded)
+fn revision_summary(record: &serde_json::Value) -> serde_json::Value {
+    let diff: serde_json::Value = record["diff"].as_str()
+        .and_then(|d| serde_json::from_str(d).ok())
+        .unwrap_or(json!([]));
+    json!({
+        "revision": record["revision"],
+        "timestamp": record["timestamp"],
+        "user": record["user"],
+        "action": record["action"],
+        "rolled_back_to": record.get("rolledBackTo"),
+        "summary": record["summary"],
+        "diff": diff,
+    })
+}
+
+/// Content of `rev`: a revision number, `<n>~` for the content before revision n,
+/// or `current` for the config.yaml on disk
+fn revision_content(revisions: &[serde_json::Value], rev: &str, config_path: &Path) -> Result<String> {
+    if rev == "current" {
+        return std::fs::read_to_string(config_path)
+            .map_err(|e| YetiError::Internal(format!("Failed to read config: {}", e)));
+    }
+    let (number, before) = match rev.strip_suffix('~') {
+        Some(n) => (n, true),
+        None => (rev, false),
+    };
+    let number: u64 = number.parse()
+        .map_err(|_| YetiError::Validation(format!("Invalid revision '{}'", rev)))?;
+    let record = revisions.iter()
+        .find(|r| r["revision"].as_u64() == Some(number))
+        .ok_or_else(|| YetiError::Validation(format!("Revision {} not found", number)))?;
+    let field = if before { "previousContent" } else { "content" };
+    Ok(record[field].as_str().unwrap_or("").to_string())
+}
+
+// ── Format-preserving config.yaml editing ──
+//
+// config.yaml files are maintained by hand, so updates are applied to the
+// original text instead of re-serialising the document. Only block mappings
+// are navigated; sequences and flow collections are rewritten as a unit.
+// Untouched lines (comments and blank lines included) are kept byte-for-byte.
+
+/// A `key: value` entry of a block mapping, located by line range
+struct YamlEntry {
+    /// Key text exactly as written (including any quotes)
+    raw_key: String,
+    key: String,
+    indent: usize,
+    /// Line of the `key:` itself
+    start: usize,
+    /// One past the last line of the value (trailing blank and comment lines excluded)
+    end: usize,
+}
+
+fn line_indent(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Lines that carry YAML content (not blank lines, comments or document markers)
+fn is_content_line(line: &str) -> bool {
+    let trimmed = line.trim();
+    !trimmed.is_empty() && !trimmed.starts_with('#') && trimmed != "---" && trimmed != "..."
+}
+
+/// Parse a `key: value` line into (raw key, key, byte offset just past the colon)
+fn parse_mapping_key(line: &str) -> Option<(String, String, usize)> {
+    let indent = line_indent(line);
+    let rest = &line[indent..];
+    if rest == "-" || rest.starts_with("- ") {
+        return None;
+    }
+
+    let (key, colon) = match rest.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let close = rest[1..].find(quote)? + 1;
+            let key = if quote == '"' {
+                serde_json::from_str::<String>(&rest[..=close]).unwrap_or_else(|_| rest[1..close].to_string())
+            } else {
+                rest[1..close].replace("''", "'")
+            };
+            (key, close + 1)
+        }
+        '{' | '[' | '&' | '*' | '!' | '|' | '>' | '%' | '@' | '`' | '?' => return None,
+        _ => {
+            let colon = rest.find(": ").or_else(|| rest.strip_suffix(':').map(|r| r.len()))?;
+            (rest[..colon].trim_end().to_string(), colon)
+        }
+    };
+
+    let after = &rest[colon..];
+    if key.is_empty() || !after.starts_with(':') || !(after.len() == 1 || after[1..].starts_with(' ')) {
+        return None;
+    }
+    Some((rest[..colon].to_string(), key, indent + colon + 1))
+}
+
+/// Entries of the block mapping spanning lines `from..to` (empty if it is not one)
+fn block_entries(lines: &[String], from: usize, to: usize) -> Vec<YamlEntry> {
+    let mut entries: Vec<YamlEntry> = Vec::new();
+    let Some(first) = (from..to).find(|&i| is_content_line(&lines[i])) else { return entries };
+    let indent = line_indent(&lines[first]);
+
+    for (i, line) in lines.iter().enumerate().take(to).skip(first) {
+        if !is_content_line(line) {
+            continue;
+        }
+        let line_indent = line_indent(line);
+        if line_indent < indent {
+            break;
+        }
+        if line_indent == indent {
+            if let Some((raw_key, key, _)) = parse_mapping_key(line) {
+                entries.push(YamlEntry { raw_key, key, indent, start: i, end: i + 1 });
+                continue;
+            }
+            // A sequence may sit at the same indent as its key (`key:\n- item`)
+            if !line.trim_start().starts_with('-') {
+                return Vec::new();
+            }
+        }
+        match entries.last_mut() {
+            Some(entry) => entry.end = i + 1,
+            None => return Vec::new(),
+        }
+    }
+    entries
+}
 
-use std::path::Path;
-use yeti_core::prelude::*;
+/// Locate the entry for a key path by descending through nested block mappings
+fn find_entry(lines: &[String], tokens: &[String]) -> Option<YamlEntry> {
+    let (mut from, mut to) = (0, lines.len());
+    let mut found = None;
+    for token in tokens {
+        let entry = block_entries(lines, from, to).into_iter().find(|e| &e.key == token)?;
+        from = entry.start + 1;
+        to = entry.end;
+        found = Some(entry);
+    }
+    found
+}
 
-pub type Apps = AppsResource;
+/// Split single-line value text into the value and a trailing `# comment`
+/// (the comment keeps its leading whitespace)
+fn split_inline_comment(text: &str) -> (&str, Option<&str>) {
+    let text = text.trim();
+    if text.starts_with('#') {
+        return ("", Some(text));
+    }
+    let search_from = match text.chars().next() {
+        Some(quote @ ('"' | '\'')) => text[1..].find(quote).map(|i| i + 2).unwrap_or(text.len()),
+        _ => 0,
+    };
+    match text[search_from..].find(" #") {
+        Some(i) => {
+            let value = text[..search_from + i].trim_end();
+            (value, Some(&text[value.len()..]))
+        }
+        None => (text, None),
+    }
+}
+
+/// Quote a mapping key only when it would not read back as the same plain string
+fn render_key(key: &str) -> String {
+    let plain = !key.is_empty()
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/'))
+        && serde_yaml::from_str::<serde_yaml::Value>(key).is_ok_and(|v| v.as_str() == Some(key));
+    if plain {
+        key.to_string()
+    } else {
+        serde_json::to_string(key).unwrap_or_else(|_| key.to_string())
+    }
+}
+
+/// Render `key: value` in block style. `previous` is the original line of a
+/// single-line entry, whose quoting style and trailing comment are kept.
+fn render_entry(raw_key: &str, value: &serde_json::Value, indent: usize, previous: Option<&str>) -> Vec<String> {
+    let pad = " ".repeat(indent);
+    let rendered = serde_yaml::to_string(value).unwrap_or_default();
+    let rendered = rendered.trim_end_matches('\n');
+    let is_scalar = !value.is_object() && !value.is_array();
+
+    let (previous_value, comment) = previous
+        .and_then(|line| parse_mapping_key(line).map(|(_, _, at)| split_inline_comment(&line[at..])))
+        .unwrap_or(("", None));
+
+    if (is_scalar && !rendered.contains('\n')) || rendered == "{}" || rendered == "[]" {
+        let mut text = match (value, previous_value.chars().next()) {
+            (serde_json::Value::String(s), Some('"')) => serde_json::to_string(s).unwrap_or_default(),
+            (serde_json::Value::String(s), Some('\'')) => format!("'{}'", s.replace('\'', "''")),
+            _ => rendered.to_string(),
+        };
+        if let Some(comment) = comment.filter(|_| is_scalar) {
+            if !comment.starts_with(' ') {
+                text.push(' ');
+            }
+            text.push_str(comment);
+        }
+        return vec![format!("{}{}: {}", pad, raw_key, text)];
+    }
+
+    let mut rendered_lines = rendered.lines();
+    let mut out = Vec::new();
+    if is_scalar {
+        // Block scalar: the `|`/`>` indicator stays on the key line
+        out.push(format!("{}{}: {}", pad, raw_key, rendered_lines.next().unwrap_or("")));
+    } else {
+        out.push(format!("{}{}:", pad, raw_key));
+    }
+    for line in rendered_lines {
+        out.push(if line.is_empty() { String::new() } else { format!("{}  {}", pad, line) });
+    }
+    out
+}
 
+fn tokens_to_pointer(tokens: &[String]) -> String {
+    tokens.iter().map(|t| format!("/{}", escape_pointer_token(t))).collect()
+}
+
+/// Rewrite the node at `tokens` to match its value in `new`. Returns false
+/// when the node cannot be located and the caller should rewrite the document.
+fn apply_yaml_change(lines: &mut Vec<String>, tokens: &[String], new: &serde_json::Value) -> bool {
+    let Some((key, parent)) = tokens.split_last() else { return false };
+    let value = new.pointer(&tokens_to_pointer(tokens));
+    let parent_value = new.pointer(&tokens_to_pointer(parent));
+
+    // Removing the last key of a nested mapping leaves `{}`, not null
+    if value.is_none() && !parent.is_empty() && parent_value.is_some_and(|v| v.as_object().is_some_and(|m| m.is_empty())) {
+        return apply_yaml_change(lines, parent, new);
+    }
+
+    match (find_entry(lines, tokens), value) {
+        (Some(entry), Some(value)) => {
+            let previous = (entry.end == entry.start + 1).then(|| lines[entry.start].clone());
+            let rendered = render_entry(&entry.raw_key, value, entry.indent, previous.as_deref());
+            lines.splice(entry.start..entry.end, rendered);
+            true
+        }
+        (Some(entry), None) => {
+            lines.drain(entry.start..entry.end);
+            true
+        }
+        (None, Some(value)) => {
+            let range = if parent.is_empty() {
+                Some((0, lines.len()))
+            } else {
+                find_entry(lines, parent).map(|e| (e.start + 1, e.end))
+            };
+            let Some((from, to)) = range else {
+                return !parent.is_empty() && apply_yaml_change(lines, parent, new);
+            };
+            let (indent, at) = match block_entries(lines, from, to).last() {
+                Some(last) => (last.indent, last.end),
+                None if parent.is_empty() => (0, lines.len()),
+                // Parent is a flow collection or empty: rewrite it whole
+                None => return apply_yaml_change(lines, parent, new),
+            };
+            lines.splice(at..at, render_entry(&render_key(key), value, indent, None));
+            true
+        }
+        (None, None) => true,
+    }
+}
+
+/// Check that YAML text parses to the expected value
+fn yaml_matches(text: &str, expected: &serde_json::Value) -> bool {
+    serde_yaml::from_str::<serde_yaml::Value>(text)
+        .ok()
+        .and_then(|yaml| serde_json::to_value(yaml).ok())
+        .is_some_and(|value| value == *expected)
+}
+
+/// Produce YAML text for `new` by editing `original` (which parses to `old`)
+/// in place. If the edit can't be made or doesn't read back as `new`, the
+/// document is re-serialised instead, which drops comments and formatting;
+/// the flag is false in that case so callers can report it.
+fn edit_yaml_preserving(original: &str, old: &serde_json::Value, new: &serde_json::Value) -> (String, bool) {
+    let full_rewrite = || (serde_yaml::to_string(new).unwrap_or_default(), false);
+    let eol = if original.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut lines: Vec<String> = original.lines().map(String::from).collect();
+
+    for change in config_diff(old, new) {
+        let tokens = change["path"].as_str().and_then(|p| parse_pointer(p).ok()).unwrap_or_default();
+        if tokens.is_empty() || !apply_yaml_change(&mut lines, &tokens, new) {
+            return full_rewrite();
+        }
+    }
+
+    let mut edited = lines.join(eol);
+    if !edited.is_empty() && (original.is_empty() || original.ends_with('\n')) {
+        edited.push_str(eol);
+    }
+    if yaml_matches(&edited, new) {
+        (edited, true)
+    } else {
+        full_rewrite()
+    }
+}
+
+/// Parse config.yaml text into JSON
+fn parse_config(content: &str) -> Result<serde_json::Value> {
+    let yaml: serde_yaml::Value = serde_yaml::from_str(content)
+        .map_err(|e| YetiError::Internal(format!("Failed to parse config: {}", e)))?;
+    serde_json::to_value(yaml)
+        .map_err(|e| YetiError::Internal(format!("JSON convert failed: {}", e)))
+}
+
+// ── config.yaml validation ──
+
+/// Shape of a value accepted for a config.yaml key
+enum ConfigKind {
+    Text,
+    Flag,
+    /// Valid identifier, unchanged by config edits
+    Identifier,
+    /// URL path starting with `/`
+    RoutePath,
+    /// List of file paths or globs
+    PathList,
+    StaticFiles,
+    Extensions,
+    Dependencies,
+}
+
+/// Typed model of the config.yaml keys Yeti understands
+const CONFIG_MODEL: &[(&str, ConfigKind)] = &[
+    ("name", ConfigKind::Text),
+    ("app_id", ConfigKind::Identifier),
+    ("version", ConfigKind::Text),
+    ("description", ConfigKind::Text),
+    ("enabled", ConfigKind::Flag),
+    ("route_prefix", ConfigKind::RoutePath),
+    ("schemas", ConfigKind::PathList),
+    ("resources", ConfigKind::PathList),
+    ("static_files", ConfigKind::StaticFiles),
+    ("extensions", ConfigKind::Extensions),
+    ("dependencies", ConfigKind::Dependencies),
+    ("extension", ConfigKind::Flag),
+];
+
+/// Keys every config.yaml must have
+const REQUIRED_CONFIG_KEYS: &[&str] = &["app_id"];
+
+/// Problems found in a config, each pointing at a JSON Pointer path
 #[derive(Default)]
-pub struct AppsResource;
+struct ConfigIssues {
+    errors: Vec<serde_json::Value>,
+    warnings: Vec<serde_json::Value>,
+}
 
-/// Recursively copy a template directory, skipping build artifacts
-fn copy_template(src: &Path, dst: &Path) -> std::io::Result<()> {
-    const SKIP_DIRS: &[&str] = &["source", "node_modules", ".git", "target", "test"];
-    const SKIP_FILES: &[&str] = &["Cargo.toml", "build.rs", ".gitignore"];
+impl ConfigIssues {
+    fn error(&mut self, path: &str, message: impl Into<String>) {
+        self.errors.push(json!({"path": path, "message": message.into()}));
+    }
 
-    std::fs::create_dir_all(dst)?;
+    fn warning(&mut self, path: &str, message: impl Into<String>) {
+        self.warnings.push(json!({"path": path, "message": message.into()}));
+    }
 
-    for entry in std::fs::read_dir(src)? {
-        let entry = entry?;
-        let file_name = entry.file_name();
-        let name = file_name.to_string_lossy();
-        let src_path = entry.path();
-        let dst_path = dst.join(&file_name);
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "valid": self.errors.is_empty(),
+            "errors": self.errors,
+            "warnings": self.warnings,
+        })
+    }
+}
 
-        if src_path.is_dir() {
-            if SKIP_DIRS.contains(&name.as_ref()) {
-                continue;
+fn type_name(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "boolean",
+        serde_json::Value::Number(_) => "number",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "list",
+        serde_json::Value::Object(_) => "mapping",
+    }
+}
+
+fn expect_string<'a>(issues: &mut ConfigIssues, path: &str, value: &'a serde_json::Value) -> Option<&'a str> {
+    if value.is_string() {
+        return value.as_str();
+    }
+    issues.error(path, format!("expected a string, found {}", type_name(value)));
+    None
+}
+
+fn expect_bool(issues: &mut ConfigIssues, path: &str, value: &serde_json::Value) {
+    if !value.is_boolean() {
+        issues.error(path, format!("expected true or false, found {}", type_name(value)));
+    }
+}
+
+fn expect_route(issues: &mut ConfigIssues, path: &str, value: &serde_json::Value) {
+    if let Some(route) = expect_string(issues, path, value) {
+        if !route.starts_with('/') {
+            issues.error(path, format!("route '{}' must start with '/'", route));
+        }
+    }
+}
+
+fn expect_string_list(issues: &mut ConfigIssues, path: &str, value: &serde_json::Value) {
+    let Some(items) = value.as_array() else {
+        issues.error(path, format!("expected a list of strings, found {}", type_name(value)));
+        return;
+    };
+    for (i, item) in items.iter().enumerate() {
+        expect_string(issues, &format!("{}/{}", path, i), item);
+    }
+}
+
+/// Get a mapping, reporting an error if the value is anything else
+fn expect_mapping<'a>(
+    issues: &mut ConfigIssues,
+    path: &str,
+    value: &'a serde_json::Value,
+) -> Option<&'a serde_json::Map<String, serde_json::Value>> {
+    if value.is_object() {
+        return value.as_object();
+    }
+    issues.error(path, format!("expected a mapping, found {}", type_name(value)));
+    None
+}
+
+fn validate_static_files(issues: &mut ConfigIssues, path: &str, value: &serde_json::Value) {
+    let Some(map) = expect_mapping(issues, path, value) else { return };
+    if !map.contains_key("path") {
+        issues.error(&format!("{}/path", path), "required key is missing");
+    }
+
+    for (key, value) in map {
+        let child = format!("{}/{}", path, escape_pointer_token(key));
+        match key.as_str() {
+            "path" | "index" => {
+                expect_string(issues, &child, value);
             }
-            copy_template(&src_path, &dst_path)?;
-        } else {
-            if SKIP_FILES.contains(&name.as_ref()) {
-                continue;
+            "route" => expect_route(issues, &child, value),
+            "notFound" => {
+                let Some(not_found) = expect_mapping(issues, &child, value) else { continue };
+                if let Some(file) = not_found.get("file") {
+                    expect_string(issues, &format!("{}/file", child), file);
+                }
+                if let Some(code) = not_found.get("statusCode") {
+                    if !code.as_u64().is_some_and(|c| (100..=599).contains(&c)) {
+                        issues.error(&format!("{}/statusCode", child), "expected an HTTP status code (100-599)");
+                    }
+                }
             }
-            std::fs::copy(&src_path, &dst_path)?;
+            "build" => {
+                let Some(build) = expect_mapping(issues, &child, value) else { continue };
+                if !build.contains_key("command") {
+                    issues.error(&format!("{}/command", child), "required key is missing");
+                }
+                for field in ["sourceDir", "command"] {
+                    if let Some(v) = build.get(field) {
+                        expect_string(issues, &format!("{}/{}", child, field), v);
+                    }
+                }
+            }
+            _ => issues.warning(&child, format!("unknown static_files key '{}'", key)),
         }
     }
-    Ok(())
+}
+
+/// Extensions are listed by name, or as a single-key mapping of name to settings
+fn validate_extensions(issues: &mut ConfigIssues, path: &str, value: &serde_json::Value) {
+    let Some(items) = value.as_array() else {
+        issues.error(path, format!("expected a list, found {}", type_name(value)));
+        return;
+    };
+    for (i, item) in items.iter().enumerate() {
+        let child = format!("{}/{}", path, i);
+        match item {
+            serde_json::Value::String(_) => {}
+            serde_json::Value::Object(map) if map.len() == 1 => {
+                if let Some((name, settings)) = map.iter().next() {
+                    if !settings.is_object() && !settings.is_null() {
+                        issues.error(
+                            &format!("{}/{}", child, escape_pointer_token(name)),
+                            format!("extension settings must be a mapping, found {}", type_name(settings)),
+                        );
+                    }
+                }
+            }
+            serde_json::Value::Object(_) => {
+                issues.error(&child, "extension entry must have exactly one name");
+            }
+            other => issues.error(&child, format!("expected an extension name or mapping, found {}", type_name(other))),
+        }
+    }
+}
+
+/// Dependencies map crate names to a version string or a mapping of options
+fn validate_dependencies(issues: &mut ConfigIssues, path: &str, value: &serde_json::Value) {
+    let Some(map) = expect_mapping(issues, path, value) else { return };
+    for (name, spec) in map {
+        let child = format!("{}/{}", path, escape_pointer_token(name));
+        match spec {
+            serde_json::Value::String(_) => {}
+            serde_json::Value::Object(options) => {
+                for field in ["version", "path", "git", "branch"] {
+                    if let Some(v) = options.get(field) {
+                        expect_string(issues, &format!("{}/{}", child, field), v);
+                    }
+                }
+                if let Some(features) = options.get("features") {
+                    expect_string_list(issues, &format!("{}/features", child), features);
+                }
+            }
+            other => issues.error(&child, format!("expected a version string or mapping, found {}", type_name(other))),
+        }
+    }
+}
+
+/// Check a config against `CONFIG_MODEL`. When `expected_id` (the `app_id` the
+/// app has now) is given, `app_id` must keep it: ids change through rename, not
+/// config edits. It may differ from the directory name (e.g. a `yeti-admin` checkout).
+fn validate_app_config(config: &serde_json::Value, expected_id: Option<&str>) -> ConfigIssues {
+    let mut issues = ConfigIssues::default();
+    let Some(map) = config.as_object() else {
+        issues.error("", format!("config.yaml must be a mapping, found {}", type_name(config)));
+        return issues;
+    };
+
+    for key in REQUIRED_CONFIG_KEYS {
+        if !map.contains_key(*key) {
+            issues.error(&format!("/{}", key), "required key is missing");
+        }
+    }
+
+    for (key, value) in map {
+        let path = format!("/{}", escape_pointer_token(key));
+        let Some((_, kind)) = CONFIG_MODEL.iter().find(|(k, _)| k == key) else {
+            if key != "admin" {
+                issues.warning(&path, format!("unknown key '{}'", key));
+            }
+            continue;
+        };
+        match kind {
+            ConfigKind::Text => {
+                expect_string(&mut issues, &path, value);
+            }
+            ConfigKind::Flag => expect_bool(&mut issues, &path, value),
+            ConfigKind::Identifier => {
+                let Some(id) = expect_string(&mut issues, &path, value) else { continue };
+                if validate_identifier(id, "app_id").is_err() {
+                    issues.error(&path, format!("'{}' is not a valid app_id", id));
+                } else if let Some(expected) = expected_id.filter(|e| *e != id) {
+                    issues.error(&path, format!("app_id is '{}'; use rename to change it", expected));
+                }
+            }
+            ConfigKind::RoutePath => expect_route(&mut issues, &path, value),
+            ConfigKind::PathList => expect_string_list(&mut issues, &path, value),
+            ConfigKind::StaticFiles => validate_static_files(&mut issues, &path, value),
+            ConfigKind::Extensions => validate_extensions(&mut issues, &path, value),
+            ConfigKind::Dependencies => validate_dependencies(&mut issues, &path, value),
+        }
+    }
+    issues
+}
+
+/// Validate a config about to be written to app `app_id`, returning the 422 body
+/// if it is rejected. New apps (no config.yaml yet) may use any valid `app_id`.
+fn config_rejection(config: &serde_json::Value, app_id: &str) -> Option<serde_json::Value> {
+    let current = read_app_config(&get_apps_directory().join(app_id))
+        .and_then(|c| c["app_id"].as_str().map(String::from));
+    let issues = validate_app_config(config, current.as_deref());
+    if issues.errors.is_empty() {
+        return None;
+    }
+    Some(json!({
+        "error": format!("config.yaml for '{}' failed validation", app_id),
+        "errors": issues.errors,
+        "warnings": issues.warnings,
+    }))
+}
+
+/// `config_rejection` for raw config.yaml bytes (file editor writes and restores)
+pub(crate) fn config_text_rejection(content: &[u8], app_id: &str) -> Option<serde_json::Value> {
+    let parsed = std::str::from_utf8(content)
+        .map_err(|_| json!({"path": "", "message": "config.yaml must be UTF-8 text"}))
+        .and_then(parse_config_for_validation);
+    match parsed {
+        Ok(config) => config_rejection(&config, app_id),
+        Err(error) => Some(json!({
+            "error": format!("config.yaml for '{}' failed validation", app_id),
+            "errors": [error],
+            "warnings": [],
+        })),
+    }
+}
+
+/// Parse YAML text for validation, reporting syntax errors with their location
+fn parse_config_for_validation(content: &str) -> std::result::Result<serde_json::Value, serde_json::Value> {
+    let yaml: serde_yaml::Value = serde_yaml::from_str(content).map_err(|e| {
+        let location = e.location();
+        json!({
+            "path": "",
+            "message": format!("invalid YAML: {}", e),
+            "line": location.as_ref().map(|l| l.line()),
+            "column": location.as_ref().map(|l| l.column()),
+        })
+    })?;
+    serde_json::to_value(yaml).map_err(|e| json!({"path": "", "message": e.to_string()}))
+}
+
+// ── Atomic writes ──
+//
+// `write_atomic`, `backup_file` and the write lock live in files.rs, which also
+// lists and restores the backups.
+
+/// Back up and atomically replace `rel_path` inside an app directory
+fn save_app_file(app_path: &Path, rel_path: &str, content: &[u8]) -> Result<()> {
+    let path = app_path.join(rel_path);
+    let app_id = app_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let _guard = FILE_WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    backup_file(&app_id, rel_path, &path).map_err(YetiError::Internal)?;
+    write_atomic(&path, content)
+        .map_err(|e| YetiError::Internal(format!("Failed to write {}: {}", rel_path, e)))
+}
+
+/// Set top-level string fields in an app's config.yaml, preserving its formatting
+fn update_config_fields(config_path: &Path, fields: &[(&str, &str)]) -> Result<()> {
+    let values: Vec<(&str, serde_json::Value)> = fields.iter().map(|(k, v)| (*k, json!(v))).collect();
+    update_config_values(config_path, &values).map(|_| ())
+}
+
+/// Like `update_config_fields` for arbitrary JSON values; returns the previous
+/// and new file content
+fn update_config_values(config_path: &Path, fields: &[(&str, serde_json::Value)]) -> Result<(String, String)> {
+    let content = std::fs::read_to_string(config_path)
+        .map_err(|e| YetiError::Internal(format!("Failed to read config: {}", e)))?;
+    let existing = parse_config(&content)?;
+
+    let mut updated = existing.clone();
+    if let Some(map) = updated.as_object_mut() {
+        for (key, value) in fields {
+            map.insert(key.to_string(), value.clone());
+        }
+    }
+
+    let issues = validate_app_config(&updated, None);
+    if !issues.errors.is_empty() {
+        let summary: Vec<String> = issues.errors.iter()
+            .map(|e| format!("{}: {}", e["path"].as_str().unwrap_or(""), e["message"].as_str().unwrap_or("")))
+            .collect();
+        return Err(YetiError::Validation(format!("config.yaml failed validation: {}", summary.join("; "))));
+    }
+
+    let (new_content, preserved) = edit_yaml_preserving(&content, &existing, &updated);
+    if !preserved {
+        yeti_log!(warn, "Rewrote {} in full; its comments and formatting were not kept", config_path.display());
+    }
+    let app_path = config_path.parent().unwrap_or(Path::new("."));
+    save_app_file(app_path, "config.yaml", new_content.as_bytes())?;
+    Ok((content, new_content))
 }
 
 /// Read and parse an app's config.yaml
@@ -97,32 +3206,422 @@ fn count_resources(app_path: &Path) -> usize {
         .unwrap_or(0)
 }
 
-/// Count @table types across all schema files referenced in config.yaml
-fn count_tables(app_path: &Path) -> usize {
-    // Read config to find schema files
-    let config = read_app_config(app_path);
+/// Schema files referenced in config.yaml, falling back to schema.graphql at the root
+fn schema_files(app_path: &Path, config: Option<&serde_json::Value>) -> Vec<PathBuf> {
     let schema_paths: Vec<String> = config
-        .as_ref()
         .and_then(|c| c.get("schemas"))
         .and_then(|v| v.as_array())
         .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
         .unwrap_or_default();
 
-    // If no schemas in config, check for schema.graphql at root
-    let paths_to_check: Vec<std::path::PathBuf> = if schema_paths.is_empty() {
+    if schema_paths.is_empty() {
         let default = app_path.join("schema.graphql");
         if default.exists() { vec![default] } else { vec![] }
     } else {
         schema_paths.iter().map(|p| app_path.join(p)).collect()
+    }
+}
+
+/// Count @table types across all schema files referenced in config.yaml
+fn count_tables(app_path: &Path) -> usize {
+    let config = read_app_config(app_path);
+
+    let mut count = 0;
+    for schema_path in schema_files(app_path, config.as_ref()) {
+        if let Ok(content) = std::fs::read_to_string(&schema_path) {
+            count += content.matches("@table").count();
+        }
+    }
+    count
+}
+
+// ── App inventory cache ──
+
+/// Cached list summary for one app, with the mtimes it was computed from
+struct InventoryEntry {
+    fingerprint: Vec<(PathBuf, Option<SystemTime>)>,
+    summary: serde_json::Value,
+}
+
+fn inventory() -> &'static Mutex<std::collections::HashMap<String, InventoryEntry>> {
+    static INVENTORY: OnceLock<Mutex<std::collections::HashMap<String, InventoryEntry>>> = OnceLock::new();
+    INVENTORY.get_or_init(|| Mutex::new(std::collections::HashMap::new()))
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Paths whose mtimes determine an app's summary: the app dir (schema.graphql
+/// added/removed), config.yaml, resources/ (files added/removed) and each schema file
+fn inventory_fingerprint(app_path: &Path, config: Option<&serde_json::Value>) -> Vec<(PathBuf, Option<SystemTime>)> {
+    let mut paths = vec![
+        app_path.to_path_buf(),
+        app_path.join("config.yaml"),
+        app_path.join("resources"),
+    ];
+    paths.extend(schema_files(app_path, config));
+    paths.into_iter().map(|p| {
+        let mtime = modified_time(&p);
+        (p, mtime)
+    }).collect()
+}
+
+fn fingerprint_current(fingerprint: &[(PathBuf, Option<SystemTime>)]) -> bool {
+    fingerprint.iter().all(|(path, mtime)| modified_time(path) == *mtime)
+}
+
+fn app_summary(app_path: &Path, id: &str, config: Option<&serde_json::Value>) -> serde_json::Value {
+    let name = config
+        .and_then(|c| c.get("name"))
+        .and_then(|v| v.as_str())
+        .unwrap_or(id)
+        .to_string();
+    let enabled = config
+        .and_then(|c| c.get("enabled"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let is_extension = config
+        .and_then(|c| c.get("extension"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    json!({
+        "app_id": id,
+        "name": name,
+        "enabled": enabled,
+        "has_schema": has_schema(app_path),
+        "resource_count": count_resources(app_path),
+        "table_count": count_tables(app_path),
+        "is_extension": is_extension,
+    })
+}
+
+/// Summaries of every app, reusing cached entries whose fingerprint still matches
+fn app_inventory(apps_path: &Path) -> Result<Vec<serde_json::Value>> {
+    let entries = std::fs::read_dir(apps_path)
+        .map_err(|e| YetiError::Internal(format!("Cannot read applications dir: {}", e)))?;
+
+    let mut cache = inventory().lock().unwrap_or_else(|e| e.into_inner());
+    let mut seen = std::collections::HashSet::new();
+    let mut apps = Vec::new();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let dir_name = entry.file_name();
+        let Some(id) = dir_name.to_str() else { continue };
+
+        // Skip hidden directories (trash, import staging)
+        if id.starts_with('.') {
+            continue;
+        }
+        seen.insert(id.to_string());
+
+        if let Some(cached) = cache.get(id) {
+            if fingerprint_current(&cached.fingerprint) {
+                apps.push(cached.summary.clone());
+                continue;
+            }
+        }
+
+        let config = read_app_config(&path);
+        let fingerprint = inventory_fingerprint(&path, config.as_ref());
+        let summary = app_summary(&path, id, config.as_ref());
+        apps.push(summary.clone());
+        cache.insert(id.to_string(), InventoryEntry { fingerprint, summary });
+    }
+
+    cache.retain(|id, _| seen.contains(id));
+    Ok(apps)
+}
+
+// ── Disk usage ──
+
+#[derive(Default)]
+struct UsageState {
+    /// app_id -> (computed_at, usage)
+    results: std::collections::HashMap<String, (u64, serde_json::Value)>,
+    /// app_ids queued or being computed
+    pending: std::collections::HashSet<String>,
+}
+
+fn usage_state() -> &'static Mutex<UsageState> {
+    static STATE: OnceLock<Mutex<UsageState>> = OnceLock::new();
+    STATE.get_or_init(|| Mutex::new(UsageState::default()))
+}
+
+/// Apparent size and file count of a directory tree (symlinks are counted, not followed)
+fn dir_usage(path: &Path) -> (u64, u64) {
+    let mut bytes = 0;
+    let mut files = 0;
+    let Ok(entries) = std::fs::read_dir(path) else { return (0, 0) };
+    for entry in entries.flatten() {
+        let Ok(meta) = entry.path().symlink_metadata() else { continue };
+        if meta.is_dir() {
+            let (b, f) = dir_usage(&entry.path());
+            bytes += b;
+            files += f;
+        } else {
+            bytes += meta.len();
+            files += 1;
+        }
+    }
+    (bytes, files)
+}
+
+fn usage_json(bytes: u64, files: u64) -> serde_json::Value {
+    json!({"bytes": bytes, "files": files})
+}
+
+/// Walk an app's directory and cache dir, splitting out build output and dependencies
+fn compute_app_usage(app_id: &str) -> serde_json::Value {
+    let app_path = get_apps_directory().join(app_id);
+    let mut breakdown = serde_json::Map::new();
+    let (mut source_bytes, mut source_files) = (0, 0);
+
+    if let Ok(entries) = std::fs::read_dir(&app_path) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Ok(meta) = entry.path().symlink_metadata() else { continue };
+            if meta.is_dir() && USAGE_SPLIT_DIRS.contains(&name.as_str()) {
+                let (b, f) = dir_usage(&entry.path());
+                breakdown.insert(name, usage_json(b, f));
+            } else if meta.is_dir() {
+                let (b, f) = dir_usage(&entry.path());
+                source_bytes += b;
+                source_files += f;
+            } else {
+                source_bytes += meta.len();
+                source_files += 1;
+            }
+        }
+    }
+    for dir in USAGE_SPLIT_DIRS {
+        breakdown.entry(dir.to_string()).or_insert_with(|| usage_json(0, 0));
+    }
+    breakdown.insert("source".to_string(), usage_json(source_bytes, source_files));
+
+    let (cache_bytes, cache_files) = dir_usage(&get_cache_directory().join(app_id));
+    breakdown.insert("cache".to_string(), usage_json(cache_bytes, cache_files));
+
+    let total_bytes: u64 = breakdown.values().filter_map(|v| v["bytes"].as_u64()).sum();
+    let total_files: u64 = breakdown.values().filter_map(|v| v["files"].as_u64()).sum();
+    breakdown.insert("total".to_string(), usage_json(total_bytes, total_files));
+    serde_json::Value::Object(breakdown)
+}
+
+/// Queue usage computation for apps not already being computed
+fn schedule_usage(app_ids: Vec<String>) {
+    let queued: Vec<String> = {
+        let mut state = usage_state().lock().unwrap_or_else(|e| e.into_inner());
+        app_ids.into_iter().filter(|id| state.pending.insert(id.clone())).collect()
+    };
+    if queued.is_empty() {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        for app_id in queued {
+            let usage = compute_app_usage(&app_id);
+            let mut state = usage_state().lock().unwrap_or_else(|e| e.into_inner());
+            state.pending.remove(&app_id);
+            state.results.insert(app_id, (now_secs(), usage));
+        }
+    });
+}
+
+/// Cached disk usage for the given apps. Missing or expired results are recomputed in
+/// the background; stale results are still returned with `"status": "stale"`.
+fn cached_usage(app_ids: &[String], refresh: bool) -> std::collections::HashMap<String, serde_json::Value> {
+    let now = now_secs();
+    let mut out = std::collections::HashMap::new();
+    let mut to_compute = Vec::new();
+    {
+        let mut state = usage_state().lock().unwrap_or_else(|e| e.into_inner());
+        state.results.retain(|id, _| get_apps_directory().join(id).is_dir());
+        for id in app_ids {
+            let value = match state.results.get(id) {
+                Some((computed_at, usage)) => {
+                    let fresh = !refresh && now.saturating_sub(*computed_at) < USAGE_TTL_SECS;
+                    if !fresh {
+                        to_compute.push(id.clone());
+                    }
+                    let mut usage = usage.clone();
+                    usage["status"] = json!(if fresh { "ready" } else { "stale" });
+                    usage["computed_at"] = json!(computed_at);
+                    usage
+                }
+                None => {
+                    to_compute.push(id.clone());
+                    json!({"status": "pending"})
+                }
+            };
+            out.insert(id.clone(), value);
+        }
+    }
+    schedule_usage(to_compute);
+    out
+}
+
+/// Parse an optional `true`/`false` query parameter
+fn parse_bool_param(query: &str, key: &str) -> Result<Option<bool>> {
+    match parse_query_param(query, key).as_deref() {
+        None | Some("") => Ok(None),
+        Some("true") => Ok(Some(true)),
+        Some("false") => Ok(Some(false)),
+        Some(other) => Err(YetiError::Validation(format!("'{}' must be true or false, got '{}'", key, other))),
+    }
+}
+
+/// Filter, sort and paginate the inventory per the list query parameters.
+/// Returns the requested page and the total number of matching apps.
+fn query_inventory(mut apps: Vec<serde_json::Value>, query: &str) -> Result<(Vec<serde_json::Value>, usize)> {
+    let enabled = parse_bool_param(query, "enabled")?;
+    let is_extension = parse_bool_param(query, "is_extension")?;
+    let needle = parse_query_param(query, "q")
+        .filter(|q| !q.is_empty())
+        .map(|q| q.to_lowercase());
+
+    apps.retain(|app| {
+        enabled.is_none_or(|want| app["enabled"].as_bool() == Some(want))
+            && is_extension.is_none_or(|want| app["is_extension"].as_bool() == Some(want))
+            && needle.as_ref().is_none_or(|needle| {
+                ["app_id", "name"].iter().any(|k| {
+                    app[*k].as_str().is_some_and(|v| v.to_lowercase().contains(needle.as_str()))
+                })
+            })
+    });
+
+    let sort = parse_query_param(query, "sort").unwrap_or_else(|| "app_id".to_string());
+    let descending = match parse_query_param(query, "order").as_deref() {
+        None | Some("asc") => false,
+        Some("desc") => true,
+        Some(other) => return Err(YetiError::Validation(format!("'order' must be asc or desc, got '{}'", other))),
     };
+    if !["app_id", "name", "resource_count", "table_count", "enabled", "is_extension"].contains(&sort.as_str()) {
+        return Err(YetiError::Validation(format!(
+            "'sort' must be one of app_id, name, resource_count, table_count, enabled, is_extension; got '{}'",
+            sort
+        )));
+    }
+    // Ties fall back to app_id so pages are stable
+    let sort_key = |app: &serde_json::Value| -> (String, String) {
+        let primary = match &app[sort.as_str()] {
+            serde_json::Value::String(s) => s.to_lowercase(),
+            serde_json::Value::Number(n) => format!("{:020}", n.as_u64().unwrap_or(0)),
+            serde_json::Value::Bool(b) => b.to_string(),
+            _ => String::new(),
+        };
+        (primary, app["app_id"].as_str().unwrap_or("").to_string())
+    };
+    apps.sort_by_cached_key(sort_key);
+    if descending {
+        apps.reverse();
+    }
+
+    let total = apps.len();
+    let offset = match parse_query_param(query, "offset") {
+        Some(v) => v.parse::<usize>()
+            .map_err(|_| YetiError::Validation(format!("'offset' must be a non-negative integer, got '{}'", v)))?,
+        None => 0,
+    };
+    let limit = match parse_query_param(query, "limit") {
+        Some(v) => Some(v.parse::<usize>()
+            .map_err(|_| YetiError::Validation(format!("'limit' must be a non-negative integer, got '{}'", v)))?),
+        None => None,
+    };
+
+    let page = apps.into_iter()
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+    Ok((page, total))
+}
+
+/// Byte ranges of the quoted `database: "..."` values inside `@table(...)` directives
+fn table_database_spans(content: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    while let Some(pos) = content[offset..].find("@table") {
+        let directive_start = offset + pos + "@table".len();
+        offset = directive_start;
+
+        let rest = &content[directive_start..];
+        if !rest.trim_start().starts_with('(') {
+            continue;
+        }
+        let Some(close) = rest.find(')') else { break };
+        let args = &rest[..close];
+        let Some(db_pos) = args.find("database:") else { continue };
+        let after = &args[db_pos + "database:".len()..];
+        let Some(q_start) = after.find('"') else { continue };
+        let Some(q_len) = after[q_start + 1..].find('"') else { continue };
+
+        let value_start = directive_start + db_pos + "database:".len() + q_start + 1;
+        spans.push((value_start, value_start + q_len));
+    }
+    spans
+}
+
+/// Database names used by `@table(database: "...")` directives, in order of appearance
+fn table_databases(content: &str) -> Vec<String> {
+    table_database_spans(content)
+        .into_iter()
+        .map(|(start, end)| content[start..end].to_string())
+        .collect()
+}
 
-    let mut count = 0;
-    for schema_path in paths_to_check {
-        if let Ok(content) = std::fs::read_to_string(&schema_path) {
-            count += content.matches("@table").count();
+/// Rewrite `@table(database: "...")` names using `mapping`.
+/// Returns None when nothing changed.
+fn rewrite_table_databases(content: &str, mapping: &std::collections::HashMap<String, String>) -> Option<String> {
+    let mut rewritten = String::with_capacity(content.len());
+    let mut last = 0;
+    let mut changed = false;
+    for (start, end) in table_database_spans(content) {
+        if let Some(new_name) = mapping.get(&content[start..end]) {
+            rewritten.push_str(&content[last..start]);
+            rewritten.push_str(new_name);
+            last = end;
+            changed = true;
         }
     }
-    count
+    rewritten.push_str(&content[last..]);
+    changed.then_some(rewritten)
+}
+
+/// `origin` remote URL of an app's git checkout, if it has one
+fn git_remote_url(app_path: &Path) -> Option<String> {
+    if !app_path.join(".git").exists() {
+        return None;
+    }
+    let output = std::process::Command::new("git")
+        .args(["-C", &app_path.to_string_lossy(), "remote", "get-url", "origin"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if url.is_empty() { None } else { Some(url) }
+}
+
+/// Repository name at the end of a git URL (`git@host:org/my-app.git` -> `my-app`)
+fn remote_repo_name(url: &str) -> Option<String> {
+    let segment = url.trim_end_matches('/').rsplit(['/', ':']).next()?;
+    let name = segment.strip_suffix(".git").unwrap_or(segment);
+    if name.is_empty() { None } else { Some(name.to_lowercase()) }
+}
+
+/// Default database name for a duplicated app: swap the app id in, or suffix it
+fn duplicate_database_name(database: &str, old_id: &str, new_id: &str) -> String {
+    if database.contains(old_id) {
+        database.replace(old_id, new_id)
+    } else {
+        format!("{}-{}", database, new_id)
+    }
 }
 
 impl Resource for AppsResource {
@@ -130,8 +3629,199 @@ impl Resource for AppsResource {
         "apps"
     }
 
-    get!(_request, ctx, {
+    get!(request, ctx, {
         let apps_path = get_apps_directory();
+        let segments = route_segments(request.uri().path());
+
+        // Available templates for new apps
+        if let ["templates"] = segments.as_slice() {
+            return reply().json(json!(list_templates()));
+        }
+
+        // Route and database collisions across all apps
+        if let ["conflicts"] = segments.as_slice() {
+            let claims = installed_claims(&[]);
+            let conflicts = detect_conflicts(&claims);
+            let routes: Vec<serde_json::Value> = claims.iter().map(|c| json!({
+                "app_id": c.app_id,
+                "route_prefix": c.route_prefix,
+                "static_route": c.static_route,
+                "tables": c.tables.iter().map(|(d, t)| json!({"database": d, "table": t})).collect::<Vec<_>>(),
+            })).collect();
+            return reply().json(json!({
+                "conflicts": conflicts,
+                "apps": routes,
+            }));
+        }
+
+        // Everything served, as a tree keyed by path segment
+        if let ["routes"] = segments.as_slice() {
+            let mut tree = RouteNode::default();
+            let mut count = 0;
+            if let Ok(entries) = std::fs::read_dir(&apps_path) {
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    let id = entry.file_name().to_string_lossy().to_string();
+                    if id.starts_with('.') || !path.is_dir() {
+                        continue;
+                    }
+                    let Some(config) = read_app_config(&path) else { continue };
+                    for route in app_routes(&id, &path, &config) {
+                        tree.insert(route);
+                        count += 1;
+                    }
+                }
+            }
+            return reply().json(json!({
+                "count": count,
+                "tree": tree.to_json("/"),
+            }));
+        }
+
+        // Disk usage ranking across all apps
+        if let ["usage"] = segments.as_slice() {
+            let refresh = parse_query_param(request.uri().query().unwrap_or(""), "refresh").as_deref() == Some("true");
+            let app_ids: Vec<String> = app_inventory(&apps_path)?
+                .iter()
+                .filter_map(|a| a["app_id"].as_str().map(String::from))
+                .collect();
+            let usage = cached_usage(&app_ids, refresh);
+
+            let mut ranked: Vec<serde_json::Value> = usage.into_iter()
+                .map(|(app_id, mut usage)| {
+                    usage["app_id"] = json!(app_id);
+                    usage
+                })
+                .collect();
+            ranked.sort_by(|a, b| {
+                let a_bytes = a.pointer("/total/bytes").and_then(|v| v.as_u64()).unwrap_or(0);
+                let b_bytes = b.pointer("/total/bytes").and_then(|v| v.as_u64()).unwrap_or(0);
+                b_bytes.cmp(&a_bytes)
+                    .then_with(|| a["app_id"].as_str().cmp(&b["app_id"].as_str()))
+            });
+            let pending = ranked.iter().filter(|u| u["status"] == "pending").count();
+            let total_bytes: u64 = ranked.iter()
+                .filter_map(|u| u.pointer("/total/bytes").and_then(|v| v.as_u64()))
+                .sum();
+
+            return reply().json(json!({
+                "apps": ranked,
+                "total_bytes": total_bytes,
+                "pending": pending,
+                "ttl_secs": USAGE_TTL_SECS,
+            }));
+        }
+
+        // Deleted apps
+        if let ["trash"] = segments.as_slice() {
+            return reply().json(json!({
+                "retention_days": trash_retention_days(),
+                "entries": list_trash(&apps_path),
+            }));
+        }
+
+        // Build jobs: list and state (logs stream from the BuildEvent table)
+        if let [app_id, "build", rest @ ..] = segments.as_slice() {
+            validate_identifier(app_id, "app_id")?;
+            let jobs = build_jobs().lock().unwrap_or_else(|e| e.into_inner());
+
+            match rest {
+                [] => {
+                    let list: Vec<serde_json::Value> = jobs.iter()
+                        .filter(|j| j.app_id == *app_id)
+                        .rev()
+                        .map(|j| j.to_json(false))
+                        .collect();
+                    return reply().json(json!({"app_id": app_id, "jobs": list}));
+                }
+                [job_id, tail @ ..] => {
+                    let Some(job) = jobs.iter().find(|j| j.id == *job_id && j.app_id == *app_id) else {
+                        return not_found(&format!("Build job '{}' not found", job_id));
+                    };
+                    match tail {
+                        [] => {
+                            let include_log = parse_query_param(request.uri().query().unwrap_or(""), "log").as_deref() == Some("true");
+                            return reply().json(job.to_json(include_log));
+                        }
+                        _ => return not_found("Unknown build route"),
+                    }
+                }
+            }
+        }
+
+        // Config revision history
+        if let [app_id, "revisions", rest @ ..] = segments.as_slice() {
+            validate_identifier(app_id, "app_id")?;
+            let config_path = apps_path.join(app_id).join("config.yaml");
+            let revisions = revision_history!(ctx, app_id).await?;
+
+            match rest {
+                [] => {
+                    let list: Vec<serde_json::Value> = revisions.iter().map(revision_summary).collect();
+                    return reply().json(json!({"app_id": app_id, "revisions": list}));
+                }
+                ["diff"] => {
+                    let query = request.uri().query().unwrap_or("");
+                    let from = parse_required_query_param(query, "from")?;
+                    let to = parse_query_param(query, "to").unwrap_or_else(|| "current".to_string());
+                    let from_content = revision_content(&revisions, &from, &config_path)?;
+                    let to_content = revision_content(&revisions, &to, &config_path)?;
+                    let diff = config_diff(&parse_config(&from_content)?, &parse_config(&to_content)?);
+                    return reply().json(json!({
+                        "app_id": app_id,
+                        "from": from,
+                        "to": to,
+                        "diff": diff,
+                        "summary": diff_summary(&diff),
+                    }));
+                }
+                [rev] => {
+                    let number: u64 = rev.parse()
+                        .map_err(|_| YetiError::Validation(format!("Invalid revision '{}'", rev)))?;
+                    let Some(record) = revisions.iter().find(|r| r["revision"].as_u64() == Some(number)) else {
+                        return not_found(&format!("Revision {} of '{}' not found", number, app_id));
+                    };
+                    let mut detail = revision_summary(record);
+                    detail["content"] = record["content"].clone();
+                    detail["previous_content"] = record["previousContent"].clone();
+                    return reply().json(detail);
+                }
+                _ => return not_found("Unknown revisions route"),
+            }
+        }
+
+        // Export app as tar.gz
+        if let [app_id, "export"] = segments.as_slice() {
+            validate_identifier(app_id, "app_id")?;
+            let app_path = apps_path.join(app_id);
+            if !app_path.is_dir() {
+                return not_found(&format!("Application '{}' not found", app_id));
+            }
+
+            // Reading and compressing the app is blocking work; keep it off the executor
+            let archive = {
+                let app_id = app_id.to_string();
+                let sink = CappedBuffer { bytes: Vec::new(), limit: MAX_IMPORT_BYTES };
+                tokio::task::spawn_blocking(move || build_app_archive(sink, &app_path, &app_id))
+                    .await
+                    .map_err(|e| YetiError::Internal(format!("Failed to build archive: {}", e)))?
+            };
+            let archive = match archive {
+                Ok(sink) => sink.bytes,
+                Err(e) if e.kind() == std::io::ErrorKind::OutOfMemory => {
+                    return reply().code(413).json(json!({
+                        "error": format!("Application '{}' does not fit in a {} byte archive", app_id, MAX_IMPORT_BYTES),
+                        "limit": MAX_IMPORT_BYTES,
+                    }));
+                }
+                Err(e) => return Err(YetiError::Internal(format!("Failed to build archive: {}", e))),
+            };
+
+            return reply()
+                .header("Content-Type", "application/gzip")
+                .header("Content-Disposition", &format!("attachment; filename=\"{}.tar.gz\"", app_id))
+                .body(archive);
+        }
 
         // Single app by path ID
         if let Some(app_id) = ctx.path_id() {
@@ -145,6 +3835,8 @@ impl Resource for AppsResource {
             let has_schema = has_schema(&app_path);
             let resource_count = count_resources(&app_path);
             let table_count = count_tables(&app_path);
+            let refresh = parse_query_param(request.uri().query().unwrap_or(""), "refresh").as_deref() == Some("true");
+            let disk_usage = cached_usage(&[app_id.to_string()], refresh).remove(app_id);
 
             return reply().json(json!({
                 "app_id": app_id,
@@ -153,73 +3845,784 @@ impl Resource for AppsResource {
                 "has_schema": has_schema,
                 "resource_count": resource_count,
                 "table_count": table_count,
+                "disk_usage": disk_usage,
             }));
         }
 
-        // List all apps
-        let entries = std::fs::read_dir(&apps_path)
-            .map_err(|e| YetiError::Internal(format!("Cannot read applications dir: {}", e)))?;
+        // List all apps (cached inventory, filtered/sorted/paginated)
+        let query = request.uri().query().unwrap_or("");
+        let (mut apps, total) = query_inventory(app_inventory(&apps_path)?, query)?;
+        let page_ids: Vec<String> = apps.iter()
+            .filter_map(|a| a["app_id"].as_str().map(String::from))
+            .collect();
+        let mut usage = cached_usage(&page_ids, false);
+        for app in &mut apps {
+            let id = app["app_id"].as_str().unwrap_or("").to_string();
+            app["disk_usage"] = usage.remove(&id).unwrap_or(serde_json::Value::Null);
+        }
 
-        let mut apps = Vec::new();
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if !path.is_dir() {
-                continue;
+        reply()
+            .header("X-Total-Count", &total.to_string())
+            .json(json!(apps))
+    });
+
+    super::audit::audited!("apps", post, request, ctx, {
+        let segments = route_segments(request.uri().path());
+
+        // Import app from tar.gz (raw request body)
+        if let ["import"] = segments.as_slice() {
+            let query = request.uri().query().unwrap_or("");
+            let bytes = request.body().to_vec();
+            if bytes.is_empty() {
+                return bad_request("Request body must be a tar.gz archive");
+            }
+            if bytes.len() > MAX_IMPORT_BYTES {
+                return bad_request(&format!("Archive exceeds {} byte limit", MAX_IMPORT_BYTES));
             }
-            let dir_name = entry.file_name();
-            let Some(id) = dir_name.to_str() else { continue };
 
-            // Skip hidden directories and non-app directories
-            if id.starts_with('.') {
-                continue;
+            // Decompressing and scanning the archive is blocking work; keep it off the executor
+            let bytes = std::sync::Arc::new(bytes);
+            let manifest = {
+                let bytes = bytes.clone();
+                tokio::task::spawn_blocking(move || inspect_archive(&bytes))
+                    .await
+                    .map_err(|e| YetiError::Internal(format!("Failed to inspect archive: {}", e)))?
+                    .map_err(YetiError::Validation)?
+            };
+
+            let config: serde_yaml::Value = serde_yaml::from_str(&manifest.config)
+                .map_err(|e| YetiError::Validation(format!("Archive config.yaml is invalid: {}", e)))?;
+            if !config.is_mapping() {
+                return bad_request("Archive config.yaml must be a mapping");
             }
+            let archived_id = config.get("app_id").and_then(|v| v.as_str()).map(String::from);
 
-            let config = read_app_config(&path);
-            let name = config
-                .as_ref()
-                .and_then(|c| c.get("name"))
+            let app_id = parse_query_param(query, "app_id")
+                .or(archived_id.clone())
+                .ok_or_else(|| YetiError::Validation("Archive config.yaml has no app_id, please provide 'app_id'".to_string()))?;
+            validate_identifier(&app_id, "app_id")?;
+            check_reserved_id(&app_id)?;
+
+            let apps_path = get_apps_directory();
+            let app_path = apps_path.join(&app_id);
+            if app_path.exists() {
+                return bad_request(&format!("Application '{}' already exists", app_id));
+            }
+
+            let mut imported_config = serde_json::to_value(&config)
+                .map_err(|e| YetiError::Internal(format!("JSON convert failed: {}", e)))?;
+            imported_config["app_id"] = json!(app_id);
+            if let Some(rejection) = config_rejection(&imported_config, &app_id) {
+                return reply().code(422).json(rejection);
+            }
+
+            // Unpack into a hidden staging directory of its own, then move into place
+            let nanos = SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+            let staging_path = apps_path.join(format!(".import-{}-{}-{}", app_id, std::process::id(), nanos));
+            let unpacked = {
+                let staging_path = staging_path.clone();
+                tokio::task::spawn_blocking(move || unpack_archive(&bytes, &manifest, &staging_path))
+                    .await
+                    .map_err(|e| YetiError::Internal(format!("Failed to unpack archive: {}", e)))?
+            };
+            let unpacked = unpacked
+                .map_err(YetiError::Validation)
+                .and_then(|_| {
+                    if archived_id.as_deref() == Some(app_id.as_str()) {
+                        Ok(())
+                    } else {
+                        update_config_fields(&staging_path.join("config.yaml"), &[("app_id", &app_id)])
+                    }
+                });
+            if let Err(e) = unpacked {
+                let _ = std::fs::remove_dir_all(&staging_path);
+                return Err(e);
+            }
+            if let Some(rejection) = conflict_rejection(app_claims(&app_id, &staging_path, &imported_config), &[]) {
+                let _ = std::fs::remove_dir_all(&staging_path);
+                return reply().code(409).json(rejection);
+            }
+            if let Err(e) = std::fs::rename(&staging_path, &app_path) {
+                let _ = std::fs::remove_dir_all(&staging_path);
+                return Err(YetiError::Internal(format!("Failed to move app into place: {}", e)));
+            }
+
+            return reply().code(201).json(json!({
+                "app_id": app_id,
+                "imported": true,
+                "renamed_from": archived_id.filter(|id| id != &app_id),
+            }));
+        }
+
+        // Restore a deleted app, optionally under a new app_id
+        if let ["trash", entry, "restore"] = segments.as_slice() {
+            let apps_path = get_apps_directory();
+            purge_expired_trash(&apps_path);
+            let entry_path = resolve_trash_entry(&apps_path, entry)?;
+            if !entry_path.is_dir() {
+                return not_found(&format!("Trash entry '{}' not found", entry));
+            }
+            let (original_id, _) = parse_trash_entry(entry).unwrap_or_default();
+
+            let body = if request.body().is_empty() { json!({}) } else { request.json_value()? };
+            let app_id = body.get("app_id")
                 .and_then(|v| v.as_str())
-                .unwrap_or(id)
+                .unwrap_or(original_id)
                 .to_string();
-            let enabled = config
-                .as_ref()
-                .and_then(|c| c.get("enabled"))
-                .and_then(|v| v.as_bool())
-                .unwrap_or(false);
-            let has_schema = has_schema(&path);
-            let resource_count = count_resources(&path);
-            let table_count = count_tables(&path);
-            let is_extension = config
-                .as_ref()
-                .and_then(|c| c.get("extension"))
-                .and_then(|v| v.as_bool())
-                .unwrap_or(false);
+            validate_identifier(&app_id, "app_id")?;
+            check_reserved_id(&app_id)?;
+
+            let app_path = apps_path.join(&app_id);
+            if app_path.exists() {
+                return reply().code(409).json(json!({
+                    "error": format!("Application '{}' already exists, restore under a different app_id", app_id),
+                    "entry": entry,
+                    "app_id": app_id,
+                }));
+            }
+
+            // Rewrite app_id while the app is still in the trash, so a failed write leaves nothing half-restored
+            let recorded_id = read_app_config(&entry_path)
+                .and_then(|c| c.get("app_id").and_then(|v| v.as_str()).map(String::from));
+            if recorded_id.as_deref().unwrap_or(original_id) != app_id {
+                update_config_fields(&entry_path.join("config.yaml"), &[("app_id", &app_id)])?;
+            }
+
+            std::fs::rename(&entry_path, &app_path)
+                .map_err(|e| YetiError::Internal(format!("Failed to restore app: {}", e)))?;
+            if let Err(e) = super::secrets::restore_app_secrets(entry, original_id, &app_id) {
+                if let Err(restore) = std::fs::rename(&app_path, &entry_path) {
+                    yeti_log!(error, "Restore rollback failed for '{}': {}", entry, restore);
+                }
+                return Err(YetiError::Internal(e));
+            }
+            if let Err(e) = super::files::restore_app_backups(entry, &app_id) {
+                yeti_log!(warn, "Failed to restore backups of '{}': {}", app_id, e);
+            }
+
+            return reply().json(json!({
+                "restored": true,
+                "entry": entry,
+                "app_id": app_id,
+            }));
+        }
+
+        // Apply one action to many apps; failures are reported per app
+        if let ["bulk"] = segments.as_slice() {
+            let body = request.json_value()?;
+            let action = body.require_str("action")?;
+            if !BULK_ACTIONS.contains(&action.as_str()) {
+                return bad_request(&format!("action must be one of: {}", BULK_ACTIONS.join(", ")));
+            }
+            let mut app_ids: Vec<String> = Vec::new();
+            for id in body.get("app_ids").and_then(|v| v.as_array()).into_iter().flatten() {
+                let id = id.as_str()
+                    .ok_or_else(|| YetiError::Validation("app_ids must be a list of strings".to_string()))?;
+                if !app_ids.iter().any(|a| a == id) {
+                    app_ids.push(id.to_string());
+                }
+            }
+            if app_ids.is_empty() {
+                return bad_request("app_ids must list at least one app");
+            }
+            if app_ids.len() > MAX_BULK_APPS {
+                return bad_request(&format!("At most {} apps per bulk request", MAX_BULK_APPS));
+            }
+            let confirmed: Vec<String> = body.get("confirm")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+                .unwrap_or_default();
+            let dry_run = body.get("dry_run").and_then(|v| v.as_bool()).unwrap_or(false);
+            let concurrency = body.get("concurrency")
+                .and_then(|v| v.as_u64())
+                .map(|n| (n as usize).clamp(1, MAX_BULK_CONCURRENCY))
+                .unwrap_or(DEFAULT_BULK_CONCURRENCY);
+            let key = body.get("key").and_then(|v| v.as_str());
+
+            // Plan every app first; only apps that pass are executed
+            let mut results: Vec<serde_json::Value> = Vec::new();
+            let mut runnable: Vec<String> = Vec::new();
+            for app_id in &app_ids {
+                match bulk_plan(&action, app_id, &confirmed) {
+                    Ok(plan) => {
+                        results.push(json!({"app_id": app_id, "status": if dry_run { "planned" } else { "pending" }, "plan": plan}));
+                        runnable.push(app_id.clone());
+                    }
+                    Err(error) => results.push(json!({"app_id": app_id, "status": "skipped", "error": error})),
+                }
+            }
+
+            if !dry_run && !runnable.is_empty() {
+                // Pulls can take minutes; the workers run on the blocking pool, not the executor
+                let outcomes = {
+                    let (action, runnable, key) = (action.clone(), runnable.clone(), key.map(String::from));
+                    tokio::task::spawn_blocking(move || run_bulk(&action, &runnable, key.as_deref(), concurrency))
+                        .await
+                        .map_err(|e| YetiError::Internal(format!("Bulk run failed: {}", e)))?
+                };
+                for (app_id, outcome) in runnable.iter().zip(outcomes) {
+                    let Some(entry) = results.iter_mut().find(|r| r["app_id"] == app_id.as_str()) else { continue };
+                    match outcome {
+                        Ok(mut detail) => {
+                            // Config edits are recorded like any other config.yaml write
+                            if let (Some(previous), Some(content)) = (
+                                detail.get("previous_content").and_then(|v| v.as_str()).map(String::from),
+                                detail.get("content").and_then(|v| v.as_str()).map(String::from),
+                            ) {
+                                let diff = match (parse_config(&previous), parse_config(&content)) {
+                                    (Ok(old), Ok(new)) => config_diff(&old, &new),
+                                    _ => Vec::new(),
+                                };
+                                let user = ctx.username().unwrap_or("unknown");
+                                let action = format!("bulk-{}", action);
+                                let recorded = record_revision!(ctx, app_id, |next| revision_record(
+                                    app_id, next, user, &action, &previous, &content, &diff,
+                                )).await;
+                                match recorded {
+                                    Ok(next) => detail["revision"] = json!(next),
+                                    Err(e) => yeti_log!(warn, "Failed to record config revision for '{}': {}", app_id, e),
+                                }
+                            }
+                            if let Some(map) = detail.as_object_mut() {
+                                map.remove("previous_content");
+                                map.remove("content");
+                            }
+                            entry["status"] = json!("ok");
+                            entry["result"] = detail;
+                        }
+                        Err(error) => {
+                            entry["status"] = json!("failed");
+                            entry["error"] = json!(error);
+                        }
+                    }
+                }
+                if action == "delete" {
+                    purge_expired_trash(&get_apps_directory());
+                }
+            }
+
+            let count = |status: &str| results.iter().filter(|r| r["status"] == status).count();
+            return reply().json(json!({
+                "action": action,
+                "dry_run": dry_run,
+                "concurrency": concurrency,
+                "succeeded": count("ok"),
+                "failed": count("failed"),
+                "skipped": count("skipped"),
+                "results": results,
+            }));
+        }
+
+        // Validate a config without writing it: {"yaml": "..."} or {"config": {...}}
+        if let ["validate-config"] = segments.as_slice() {
+            let body = request.json_value()?;
+            let expected_id = body.get("app_id").and_then(|v| v.as_str());
+
+            let config = if let Some(yaml) = body.get("yaml").and_then(|v| v.as_str()) {
+                match parse_config_for_validation(yaml) {
+                    Ok(config) => config,
+                    Err(error) => {
+                        return reply().json(json!({
+                            "valid": false,
+                            "errors": [error],
+                            "warnings": [],
+                        }));
+                    }
+                }
+            } else if let Some(config) = body.get("config") {
+                config.clone()
+            } else {
+                return bad_request("Provide 'yaml' (config.yaml text) or 'config' (parsed config)");
+            };
+
+            return reply().json(validate_app_config(&config, expected_id).to_json());
+        }
+
+        // Duplicate an app under a new id
+        if let [source_id, "duplicate"] = segments.as_slice() {
+            let body = request.json_value()?;
+            let new_id = body.require_str("new_id")?;
+            validate_identifier(source_id, "app_id")?;
+            validate_identifier(&new_id, "new_id")?;
+            check_reserved_id(&new_id)?;
+
+            let apps_path = get_apps_directory();
+            let source_path = apps_path.join(source_id);
+            let app_path = apps_path.join(&new_id);
+            if !source_path.is_dir() {
+                return not_found(&format!("Application '{}' not found", source_id));
+            }
+            if app_path.exists() {
+                return bad_request(&format!("Application '{}' already exists", new_id));
+            }
+
+            let source_config = read_app_config(&source_path);
+            let source_name = source_config.as_ref()
+                .and_then(|c| c.get("name"))
+                .and_then(|v| v.as_str())
+                .unwrap_or(source_id);
+            let name = body.get("name")
+                .and_then(|v| v.as_str())
+                .map(String::from)
+                .unwrap_or_else(|| format!("{} (copy)", source_name));
+            let route_prefix = body.get("route_prefix")
+                .and_then(|v| v.as_str())
+                .map(String::from)
+                .or_else(|| {
+                    source_config.as_ref()
+                        .and_then(|c| c.get("route_prefix"))
+                        .map(|_| format!("/{}", new_id))
+                });
+
+            // Database renames: explicit mapping, or derived from the new id when requested
+            let schema_paths = schema_files(&source_path, source_config.as_ref());
+            let mut databases: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+            if let Some(explicit) = body.get("databases").and_then(|v| v.as_object()) {
+                for (old_name, new_name) in explicit {
+                    let new_name = new_name.as_str()
+                        .ok_or_else(|| YetiError::Validation(format!("databases.{} must be a string", old_name)))?;
+                    validate_identifier(new_name, "database")?;
+                    databases.insert(old_name.clone(), new_name.to_string());
+                }
+            } else if body.get("rewrite_databases").and_then(|v| v.as_bool()).unwrap_or(false) {
+                for schema_path in &schema_paths {
+                    let content = std::fs::read_to_string(schema_path).unwrap_or_default();
+                    for database in table_databases(&content) {
+                        let new_name = duplicate_database_name(&database, source_id, &new_id);
+                        databases.entry(database).or_insert(new_name);
+                    }
+                }
+            }
 
-            apps.push(json!({
-                "app_id": id,
+            // Check the copy's claims (new id, route prefix, renamed databases) before copying anything
+            if let Some(config) = &source_config {
+                let mut planned = config.clone();
+                planned["app_id"] = json!(new_id);
+                if let Some(prefix) = &route_prefix {
+                    planned["route_prefix"] = json!(prefix);
+                }
+                let mut claims = app_claims(&new_id, &source_path, &planned);
+                for (database, _) in &mut claims.tables {
+                    if let Some(renamed) = databases.get(database.as_str()) {
+                        *database = renamed.clone();
+                    }
+                }
+                claims.tables.sort();
+                claims.tables.dedup();
+                if let Some(rejection) = conflict_rejection(claims, &[]) {
+                    return reply().code(409).json(rejection);
+                }
+            }
+
+            copy_dir_filtered(&source_path, &app_path, &[], &[])
+                .map_err(|e| {
+                    let _ = std::fs::remove_dir_all(&app_path);
+                    YetiError::Internal(format!("Failed to copy app: {}", e))
+                })?;
+
+            let rewrite = || -> Result<Vec<String>> {
+                let mut fields: Vec<(&str, &str)> = vec![("app_id", &new_id), ("name", &name)];
+                if let Some(prefix) = &route_prefix {
+                    fields.push(("route_prefix", prefix));
+                }
+                update_config_fields(&app_path.join("config.yaml"), &fields)?;
+                let mut rewritten = vec!["config.yaml".to_string()];
+
+                if !databases.is_empty() {
+                    for schema_path in &schema_paths {
+                        let Ok(rel) = schema_path.strip_prefix(&source_path) else { continue };
+                        let target = app_path.join(rel);
+                        let Ok(content) = std::fs::read_to_string(&target) else { continue };
+                        if let Some(updated) = rewrite_table_databases(&content, &databases) {
+                            // A symlinked schema is shared with the source; the copy gets its own file
+                            if target.symlink_metadata().is_ok_and(|m| m.file_type().is_symlink()) {
+                                std::fs::remove_file(&target)
+                                    .map_err(|e| YetiError::Internal(format!("Failed to replace {}: {}", rel.display(), e)))?;
+                            }
+                            write_atomic(&target, updated.as_bytes())
+                                .map_err(|e| YetiError::Internal(format!("Failed to write {}: {}", rel.display(), e)))?;
+                            rewritten.push(rel.to_string_lossy().to_string());
+                        }
+                    }
+                }
+                Ok(rewritten)
+            };
+            let rewritten_files = match rewrite() {
+                Ok(files) => files,
+                Err(e) => {
+                    let _ = std::fs::remove_dir_all(&app_path);
+                    return Err(e);
+                }
+            };
+
+            return reply().code(201).json(json!({
+                "app_id": new_id,
+                "source": source_id,
                 "name": name,
-                "enabled": enabled,
-                "has_schema": has_schema,
-                "resource_count": resource_count,
-                "table_count": table_count,
-                "is_extension": is_extension,
+                "route_prefix": route_prefix,
+                "databases": databases,
+                "rewritten_files": rewritten_files,
             }));
         }
 
-        apps.sort_by(|a, b| {
-            let a_id = a["app_id"].as_str().unwrap_or("");
-            let b_id = b["app_id"].as_str().unwrap_or("");
-            a_id.cmp(b_id)
-        });
+        // Generate resources/<table>.rs for a schema table
+        if let [app_id, "scaffold"] = segments.as_slice() {
+            validate_identifier(app_id, "app_id")?;
+            let body = request.json_value()?;
+            let table_name = body.require_str("table")?;
+            validate_identifier(&table_name, "table")?;
+            let dry_run = body.get("dry_run").and_then(|v| v.as_bool()).unwrap_or(false)
+                || parse_query_param(request.uri().query().unwrap_or(""), "dry_run").as_deref() == Some("true");
+            let overwrite = body.get("overwrite").and_then(|v| v.as_bool()).unwrap_or(false);
 
-        reply().json(json!(apps))
-    });
+            let app_path = get_apps_directory().join(app_id);
+            let Some(config) = read_app_config(&app_path) else {
+                return not_found(&format!("Application '{}' not found", app_id));
+            };
+
+            let mut table = None;
+            let mut available = Vec::new();
+            for schema_path in schema_files(&app_path, Some(&config)) {
+                let Ok(content) = std::fs::read_to_string(&schema_path) else { continue };
+                available.extend(schema_tables(&content).into_iter().map(|(name, _)| name));
+                if table.is_none() {
+                    table = find_table(&content, &table_name);
+                }
+            }
+            let Some(mut table) = table else {
+                return reply().code(404).json(json!({
+                    "error": format!("Table '{}' not found in {}'s schemas", table_name, app_id),
+                    "tables": available,
+                }));
+            };
+            if table.database.is_empty() {
+                table.database = app_id.to_string();
+            }
+
+            let route_prefix = config.get("route_prefix")
+                .and_then(|v| v.as_str())
+                .map(String::from)
+                .unwrap_or_else(|| format!("/{}", app_id));
+            let code = render_resource_scaffold(&table, &route_prefix);
+            let rel_path = format!("resources/{}.rs", snake_case(&table.name));
+            let target = app_path.join(&rel_path);
+            let exists = target.exists();
+
+            let mut warnings = Vec::new();
+            let resource_globs: Vec<&str> = config.get("resources")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
+                .unwrap_or_default();
+            if !resource_globs.iter().any(|g| *g == "resources/*.rs" || *g == rel_path) {
+                warnings.push(format!("config.yaml 'resources' does not include {}", rel_path));
+            }
+            let fields: Vec<serde_json::Value> = table.fields.iter().map(|f| json!({
+                "name": f.name,
+                "type": f.base_type,
+                "required": f.required,
+                "list": f.list,
+                "validated": json_type_check(&f.base_type).is_some(),
+            })).collect();
+
+            if dry_run {
+                return reply().json(json!({
+                    "app_id": app_id,
+                    "table": table.name,
+                    "path": rel_path,
+                    "exists": exists,
+                    "fields": fields,
+                    "code": code,
+                    "warnings": warnings,
+                    "dry_run": true,
+                }));
+            }
+            if exists && !overwrite {
+                return reply().code(409).json(json!({
+                    "error": format!("{} already exists; pass overwrite: true to replace it", rel_path),
+                    "path": rel_path,
+                }));
+            }
+
+            std::fs::create_dir_all(app_path.join("resources"))
+                .map_err(|e| YetiError::Internal(format!("Failed to create resources dir: {}", e)))?;
+            save_app_file(&app_path, &rel_path, code.as_bytes())?;
+
+            return reply().code(201).json(json!({
+                "app_id": app_id,
+                "table": table.name,
+                "path": rel_path,
+                "fields": fields,
+                "warnings": warnings,
+                "created": true,
+            }));
+        }
+
+        // cargo check the app's resources, returning structured diagnostics
+        if let [app_id, "check"] = segments.as_slice() {
+            validate_identifier(app_id, "app_id")?;
+            let app_path = get_apps_directory().join(app_id);
+            if !app_path.is_dir() {
+                return not_found(&format!("Application '{}' not found", app_id));
+            }
+
+            return match compile_check(app_id).await {
+                Ok(result) => reply().json(result),
+                Err(RunError::Timeout(e)) => reply().code(504).json(json!({"error": e, "app_id": app_id})),
+                Err(RunError::Busy(e)) => reply().code(409).json(json!({"error": e, "app_id": app_id})),
+                Err(RunError::Failed(e)) => bad_request(&e),
+            };
+        }
+
+        // Run the app's static_files build command in the background
+        if let [app_id, "build"] = segments.as_slice() {
+            validate_identifier(app_id, "app_id")?;
+            let app_path = get_apps_directory().join(app_id);
+            let Some(config) = read_app_config(&app_path) else {
+                return not_found(&format!("Application '{}' not found", app_id));
+            };
+
+            let static_files = config.get("static_files");
+            let Some(command) = static_files
+                .and_then(|sf| sf.pointer("/build/command"))
+                .and_then(|v| v.as_str())
+            else {
+                return bad_request(&format!("Application '{}' declares no static_files.build.command", app_id));
+            };
+            let source_dir = static_files
+                .and_then(|sf| sf.pointer("/build/sourceDir"))
+                .and_then(|v| v.as_str())
+                .unwrap_or(".");
+            let source_path = validate_path_within_base(&app_path, source_dir)?;
+            if !source_path.is_dir() {
+                return bad_request(&format!("Build sourceDir '{}' does not exist", source_dir));
+            }
+            let web_dir = static_files
+                .and_then(|sf| sf.get("path"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("web");
+
+            let body = request.json_value().unwrap_or(json!({}));
+            let max_timeout = build_timeout_secs();
+            let timeout_secs = body.get("timeout_secs")
+                .and_then(|v| v.as_u64())
+                .map(|t| t.min(max_timeout))
+                .unwrap_or(max_timeout);
+
+            // The job thread queues BuildEvent writes; this task applies them until the job ends
+            let build_events = ctx.get_table("BuildEvent")?;
+            let (events, mut writes) = tokio::sync::mpsc::unbounded_channel::<(String, Option<serde_json::Value>)>();
+            tokio::spawn(async move {
+                while let Some((id, record)) = writes.recv().await {
+                    let written = match record {
+                        Some(record) => build_events.put(&id, record).await,
+                        None => build_events.delete(&id).await,
+                    };
+                    if let Err(e) = written {
+                        yeti_log!(warn, "Failed to write build event {}: {}", id, e);
+                    }
+                }
+            });
+
+            return match start_build_job(app_id, source_path, app_path.join(web_dir), command.to_string(), source_dir.to_string(), timeout_secs, events) {
+                Ok(job_id) => reply().code(202).json(json!({
+                    "job_id": job_id,
+                    "app_id": app_id,
+                    "status": "queued",
+                    "timeout_secs": timeout_secs,
+                })),
+                Err(running) => reply().code(409).json(json!({
+                    "error": format!("A build is already running for '{}'", app_id),
+                    "job_id": running,
+                })),
+            };
+        }
+
+        // Roll config.yaml back to a recorded revision (recorded as a new revision)
+        if let [app_id, "revisions", rev, "rollback"] = segments.as_slice() {
+            validate_identifier(app_id, "app_id")?;
+            let number: u64 = rev.parse()
+                .map_err(|_| YetiError::Validation(format!("Invalid revision '{}'", rev)))?;
+            let config_path = get_apps_directory().join(app_id).join("config.yaml");
+            if !config_path.exists() {
+                return not_found(&format!("Application '{}' not found", app_id));
+            }
+
+            let revisions = revision_history!(ctx, app_id).await?;
+            let Some(target) = revisions.iter().find(|r| r["revision"].as_u64() == Some(number)) else {
+                return not_found(&format!("Revision {} of '{}' not found", number, app_id));
+            };
+            let target_content = target["content"].as_str().unwrap_or("").to_string();
+
+            let current_content = std::fs::read_to_string(&config_path)
+                .map_err(|e| YetiError::Internal(format!("Failed to read config: {}", e)))?;
+            let current = parse_config(&current_content)?;
+            let restored = parse_config(&target_content)?;
+            if let Some(rejection) = config_rejection(&restored, app_id) {
+                return reply().code(422).json(rejection);
+            }
+            let app_path = get_apps_directory().join(app_id);
+            if let Some(rejection) = conflict_rejection(app_claims(app_id, &app_path, &restored), &[]) {
+                return reply().code(409).json(rejection);
+            }
+
+            // Restore the exact text of the revision, comments included
+            save_app_file(&app_path, "config.yaml", target_content.as_bytes())?;
+
+            let diff = config_diff(&current, &restored);
+            let user = ctx.username().unwrap_or("unknown");
+            let next = record_revision!(ctx, app_id, |next| {
+                let mut record = revision_record(app_id, next, user, "rollback", &current_content, &target_content, &diff);
+                record["rolledBackTo"] = json!(number);
+                record
+            }).await?;
+
+            return reply().json(json!({
+                "app_id": app_id,
+                "rolled_back_to": number,
+                "revision": next,
+                "config": restored,
+                "diff": diff,
+            }));
+        }
+
+        // Rename: move dir, cache and secrets, rewrite app_id, roll back if the config can't be written
+        if let [old_id, "rename"] = segments.as_slice() {
+            let body = request.json_value()?;
+            let new_id = body.require_str("new_id")?;
+            validate_identifier(old_id, "app_id")?;
+            validate_identifier(&new_id, "new_id")?;
+            check_reserved_id(&new_id)?;
+
+            if admin_app_dir().as_deref() == Some(*old_id) {
+                return bad_request(&format!("The '{}' app cannot be renamed", old_id));
+            }
+            if *old_id == new_id {
+                return bad_request("new_id must differ from the current app_id");
+            }
+
+            // Renaming breaks every route and reference to the app, so it follows the delete policy
+            let confirm = parse_query_param(request.uri().query().unwrap_or(""), "confirm")
+                .or_else(|| body.get("confirm").and_then(|v| v.as_str()).map(String::from));
+            if let Some(rejection) = protection_rejection(old_id, "renamed", confirm.as_deref()) {
+                return reply().code(403).json(rejection);
+            }
+
+            let apps_path = get_apps_directory();
+            let old_path = apps_path.join(old_id);
+            let new_path = apps_path.join(&new_id);
+            if !old_path.is_dir() {
+                return not_found(&format!("Application '{}' not found", old_id));
+            }
+            if new_path.exists() {
+                return reply().code(409).json(json!({
+                    "error": format!("Application '{}' already exists", new_id),
+                }));
+            }
+            if !old_path.join("config.yaml").is_file() {
+                return bad_request(&format!("Application '{}' has no config.yaml", old_id));
+            }
+
+            if let Some(mut config) = read_app_config(&old_path) {
+                config["app_id"] = json!(new_id);
+                if let Some(rejection) = conflict_rejection(app_claims(&new_id, &old_path, &config), &[old_id]) {
+                    return reply().code(409).json(rejection);
+                }
+            }
+
+            std::fs::rename(&old_path, &new_path)
+                .map_err(|e| YetiError::Internal(format!("Failed to move app directory: {}", e)))?;
+
+            // Cached build output follows the app. A stale entry under the new id is set
+            // aside rather than deleted, and only removed once the rename has succeeded.
+            let cache_dir = get_cache_directory();
+            let old_cache = cache_dir.join(old_id);
+            let new_cache = cache_dir.join(&new_id);
+            let mut cache_moved = false;
+            let mut stale_cache = None;
+            if old_cache.is_dir() {
+                if new_cache.exists() {
+                    let nanos = SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+                    let aside = cache_dir.join(format!(".{}.stale-{}", new_id, nanos));
+                    if std::fs::rename(&new_cache, &aside).is_ok() {
+                        stale_cache = Some(aside);
+                    }
+                }
+                cache_moved = !new_cache.exists() && std::fs::rename(&old_cache, &new_cache).is_ok();
+            }
+
+            // Secrets are sealed to the app id, so they are re-sealed for the new one
+            let renamed = super::secrets::rename_app_secrets(old_id, &new_id)
+                .map_err(YetiError::Internal)
+                .and_then(|_| {
+                    update_config_fields(&new_path.join("config.yaml"), &[("app_id", &new_id)])
+                        .inspect_err(|_| {
+                            if let Err(e) = super::secrets::rename_app_secrets(&new_id, old_id) {
+                                yeti_log!(error, "Rename rollback failed for the secrets of '{}': {}", old_id, e);
+                            }
+                        })
+                });
+            if let Err(e) = renamed {
+                if cache_moved {
+                    let _ = std::fs::rename(&new_cache, &old_cache);
+                }
+                if let Some(aside) = &stale_cache {
+                    let _ = std::fs::rename(aside, &new_cache);
+                }
+                if let Err(restore) = std::fs::rename(&new_path, &old_path) {
+                    yeti_log!(error, "Rename rollback failed for '{}' -> '{}': {}", old_id, new_id, restore);
+                }
+                return Err(e);
+            }
+            if let Some(aside) = stale_cache {
+                let _ = std::fs::remove_dir_all(aside);
+            }
+
+            if let Err(e) = super::files::rename_app_backups(old_id, &new_id) {
+                yeti_log!(warn, "Failed to move backups of '{}' to '{}': {}", old_id, new_id, e);
+            }
+
+            let mut warnings = Vec::new();
+            let revisions_moved = match migrate_revisions!(ctx, old_id, &new_id).await {
+                Ok(moved) => moved,
+                Err(e) => {
+                    yeti_log!(warn, "Failed to move config revisions of '{}' to '{}': {}", old_id, new_id, e);
+                    warnings.push(format!("config revision history is still recorded under '{}'", old_id));
+                    0
+                }
+            };
+            let remote_url = git_remote_url(&new_path);
+            if let Some(url) = &remote_url {
+                if remote_repo_name(url).as_deref() == Some(old_id.to_lowercase().as_str()) {
+                    warnings.push(format!(
+                        "git remote 'origin' ({}) still points at a repository named '{}'; rename the repository or update the remote",
+                        url, old_id
+                    ));
+                }
+            }
+            if let Some(prefix) = read_app_config(&new_path)
+                .and_then(|c| c.get("route_prefix").and_then(|v| v.as_str()).map(String::from))
+            {
+                if prefix.trim_matches('/') == *old_id {
+                    warnings.push(format!("route_prefix is still '{}'", prefix));
+                }
+            }
+
+            return reply().json(json!({
+                "app_id": new_id,
+                "previous_app_id": old_id,
+                "renamed": true,
+                "cache_moved": cache_moved,
+                "revisions_moved": revisions_moved,
+                "remote_url": remote_url,
+                "warnings": warnings,
+            }));
+        }
 
-    post!(request, _ctx, {
         let body = request.json_value()?;
         let app_id = body.require_str("id")?;
 
         validate_identifier(&app_id, "app_id")?;
+        check_reserved_id(&app_id)?;
 
         let name = body.get("name").and_then(|v| v.as_str()).unwrap_or(&app_id);
         let description = body.get("description").and_then(|v| v.as_str()).unwrap_or("A new Yeti application");
@@ -244,31 +4647,17 @@ impl Resource for AppsResource {
                 .map_err(|e| YetiError::Internal(format!("Failed to copy template: {}", e)))?;
 
             // Update config.yaml with new app_id/name/description
-            let config_path = app_path.join("config.yaml");
-            let content = std::fs::read_to_string(&config_path)
-                .map_err(|e| YetiError::Internal(format!("Failed to read template config: {}", e)))?;
-            let mut yaml: serde_yaml::Value = serde_yaml::from_str(&content)
-                .map_err(|e| YetiError::Internal(format!("Failed to parse template config: {}", e)))?;
-
-            if let Some(map) = yaml.as_mapping_mut() {
-                map.insert(
-                    serde_yaml::Value::String("app_id".to_string()),
-                    serde_yaml::Value::String(app_id.to_string()),
-                );
-                map.insert(
-                    serde_yaml::Value::String("name".to_string()),
-                    serde_yaml::Value::String(name.to_string()),
-                );
-                map.insert(
-                    serde_yaml::Value::String("description".to_string()),
-                    serde_yaml::Value::String(description.to_string()),
-                );
-            }
-
-            let new_content = serde_yaml::to_string(&yaml)
-                .map_err(|e| YetiError::Internal(format!("Failed to serialize config: {}", e)))?;
-            std::fs::write(&config_path, &new_content)
-                .map_err(|e| YetiError::Internal(format!("Failed to write config: {}", e)))?;
+            update_config_fields(&app_path.join("config.yaml"), &[
+                ("app_id", &app_id),
+                ("name", name),
+                ("description", description),
+            ])?;
+            if let Some(rejection) = read_app_config(&app_path)
+                .and_then(|config| conflict_rejection(app_claims(&app_id, &app_path, &config), &[]))
+            {
+                let _ = std::fs::remove_dir_all(&app_path);
+                return reply().code(409).json(rejection);
+            }
 
             reply().code(201).json(json!({
                 "app_id": app_id,
@@ -277,19 +4666,70 @@ impl Resource for AppsResource {
                 "template": "application-template",
                 "created": true,
             }))
+        } else if let Some(template_id) = template.filter(|t| *t != "blank") {
+            // Copy from a registered template, filling its variables
+            validate_identifier(template_id, "template")?;
+            let template_path = templates_directory().join(template_id);
+            if !template_path.join(TEMPLATE_MANIFEST).is_file() {
+                return bad_request(&format!("Unknown template '{}'", template_id));
+            }
+            let manifest = read_template_manifest(&template_path, template_id)
+                .map_err(YetiError::Internal)?;
+
+            let provided = body.get("variables").and_then(|v| v.as_object());
+            let values = match resolve_template_variables(&manifest, provided, &[
+                ("app_id", &app_id),
+                ("name", name),
+                ("description", description),
+            ]) {
+                Ok(values) => values,
+                Err(errors) => {
+                    return reply().code(422).json(json!({
+                        "error": format!("Invalid variables for template '{}'", template_id),
+                        "errors": errors,
+                    }));
+                }
+            };
+
+            let created = copy_template_with_variables(&template_path, &app_path, &values, true)
+                .map_err(|e| YetiError::Internal(format!("Failed to copy template: {}", e)))
+                .and_then(|_| {
+                    if !app_path.join("config.yaml").is_file() {
+                        return Err(YetiError::Internal(format!("Template '{}' has no config.yaml", template_id)));
+                    }
+                    update_config_fields(&app_path.join("config.yaml"), &[
+                        ("app_id", &app_id),
+                        ("name", name),
+                        ("description", description),
+                    ])
+                });
+            if let Err(e) = created {
+                let _ = std::fs::remove_dir_all(&app_path);
+                return Err(e);
+            }
+            if let Some(rejection) = read_app_config(&app_path)
+                .and_then(|config| conflict_rejection(app_claims(&app_id, &app_path, &config), &[]))
+            {
+                let _ = std::fs::remove_dir_all(&app_path);
+                return reply().code(409).json(rejection);
+            }
+
+            reply().code(201).json(json!({
+                "app_id": app_id,
+                "name": name,
+                "description": description,
+                "template": template_id,
+                "variables": values,
+                "created": true,
+            }))
         } else {
             // Create blank app from inline template
-            std::fs::create_dir_all(app_path.join("resources"))
-                .map_err(|e| YetiError::Internal(format!("Failed to create directories: {}", e)))?;
-            std::fs::create_dir_all(app_path.join("web"))
-                .map_err(|e| YetiError::Internal(format!("Failed to create web dir: {}", e)))?;
-
             let config_content = format!(
                 r#"# Application metadata
-name: "{}"
+name: {}
 app_id: "{}"
 version: "1.0.0"
-description: "{}"
+description: {}
 
 # Application state
 enabled: true
@@ -303,10 +4743,25 @@ static_files:
   path: web
   route: /
 "#,
-                name, app_id, description
+                serde_json::to_string(name).unwrap_or_default(),
+                app_id,
+                serde_json::to_string(description).unwrap_or_default(),
             );
 
-            std::fs::write(app_path.join("config.yaml"), &config_content)
+            let config = parse_config(&config_content)?;
+            if let Some(rejection) = config_rejection(&config, &app_id) {
+                return reply().code(422).json(rejection);
+            }
+            if let Some(rejection) = conflict_rejection(app_claims(&app_id, &app_path, &config), &[]) {
+                return reply().code(409).json(rejection);
+            }
+
+            std::fs::create_dir_all(app_path.join("resources"))
+                .map_err(|e| YetiError::Internal(format!("Failed to create directories: {}", e)))?;
+            std::fs::create_dir_all(app_path.join("web"))
+                .map_err(|e| YetiError::Internal(format!("Failed to create web dir: {}", e)))?;
+
+            write_atomic(&app_path.join("config.yaml"), config_content.as_bytes())
                 .map_err(|e| YetiError::Internal(format!("Failed to write config: {}", e)))?;
 
             let index_html = format!(
@@ -316,7 +4771,7 @@ static_files:
 </html>"#,
                 name, name, description
             );
-            std::fs::write(app_path.join("web").join("index.html"), &index_html)
+            write_atomic(&app_path.join("web").join("index.html"), index_html.as_bytes())
                 .map_err(|e| YetiError::Internal(format!("Failed to write index.html: {}", e)))?;
 
             reply().code(201).json(json!({
@@ -328,8 +4783,14 @@ static_files:
         }
     });
 
-    put!(request, ctx, {
+    super::audit::audited!("apps", put, request, ctx, {
         let app_id = ctx.require_id()?.to_string();
+        let query = request.uri().query().unwrap_or("");
+        let dry_run = parse_query_param(query, "dry_run").is_some_and(|v| v == "true" || v == "1");
+        let is_json_patch = request.headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.starts_with("application/json-patch+json"));
         let body = request.json_value()?;
 
         let apps_path = get_apps_directory();
@@ -343,49 +4804,94 @@ static_files:
         // Read existing config
         let existing_content = std::fs::read_to_string(&config_path)
             .map_err(|e| YetiError::Internal(format!("Failed to read config: {}", e)))?;
-        let mut existing: serde_yaml::Value = serde_yaml::from_str(&existing_content)
-            .map_err(|e| YetiError::Internal(format!("Failed to parse config: {}", e)))?;
+        let existing = parse_config(&existing_content)?;
 
-        // Convert body to serde_yaml::Value for merging
-        let body_str = serde_json::to_string(&body)
-            .map_err(|e| YetiError::Internal(format!("JSON serialize failed: {}", e)))?;
-        let updates: serde_yaml::Value = serde_yaml::from_str(&body_str)
-            .map_err(|e| YetiError::Internal(format!("YAML parse failed: {}", e)))?;
+        // Apply the patch to a copy so a failing JSON Patch leaves nothing half-applied
+        let mut updated = existing.clone();
+        if is_json_patch {
+            apply_json_patch(&mut updated, &body)
+                .map_err(|e| YetiError::Validation(format!("JSON Patch failed: {}", e)))?;
+        } else {
+            merge_patch(&mut updated, &body);
+        }
 
-        // Merge updates into existing (top-level keys only)
-        if let (Some(existing_map), Some(updates_map)) = (existing.as_mapping_mut(), updates.as_mapping()) {
-            for (key, value) in updates_map {
-                existing_map.insert(key.clone(), value.clone());
-            }
+        if !updated.is_object() {
+            return bad_request("config.yaml must remain a mapping");
+        }
+
+        let diff = config_diff(&existing, &updated);
+        let issues = validate_app_config(&updated, existing["app_id"].as_str());
+        // Only the changed nodes are rewritten; when that isn't possible the whole
+        // file is re-serialised and the response says its formatting was lost
+        let (new_content, formatting_preserved) = edit_yaml_preserving(&existing_content, &existing, &updated);
+
+        if dry_run {
+            return reply().json(json!({
+                "app_id": app_id,
+                "config": updated,
+                "diff": diff,
+                "validation": issues.to_json(),
+                "formatting_preserved": formatting_preserved,
+                "dry_run": true,
+                "updated": false,
+            }));
+        }
+
+        if let Some(rejection) = config_rejection(&updated, &app_id) {
+            return reply().code(422).json(rejection);
+        }
+        if let Some(rejection) = conflict_rejection(app_claims(&app_id, &app_path, &updated), &[]) {
+            return reply().code(409).json(rejection);
         }
 
-        // Write back
-        let new_content = serde_yaml::to_string(&existing)
-            .map_err(|e| YetiError::Internal(format!("Failed to serialize config: {}", e)))?;
-        std::fs::write(&config_path, &new_content)
-            .map_err(|e| YetiError::Internal(format!("Failed to write config: {}", e)))?;
+        save_app_file(&app_path, "config.yaml", new_content.as_bytes())?;
 
-        // Return updated config as JSON
-        let json_str = serde_json::to_string(&existing)
-            .map_err(|e| YetiError::Internal(format!("JSON serialize failed: {}", e)))?;
-        let json_val: serde_json::Value = serde_json::from_str(&json_str)
-            .map_err(|e| YetiError::Internal(format!("JSON parse failed: {}", e)))?;
+        // Record the revision; the write has already happened, so a history failure is only logged
+        let user = ctx.username().unwrap_or("unknown");
+        let revision = match record_revision!(ctx, &app_id, |next| revision_record(
+            &app_id, next, user, "update", &existing_content, &new_content, &diff,
+        )).await {
+            Ok(next) => Some(next),
+            Err(e) => {
+                yeti_log!(warn, "Failed to record config revision for '{}': {}", app_id, e);
+                None
+            }
+        };
 
         reply().json(json!({
             "app_id": app_id,
-            "config": json_val,
+            "config": updated,
+            "diff": diff,
+            "formatting_preserved": formatting_preserved,
             "updated": true,
+            "revision": revision,
         }))
     });
 
-    delete!(_request, ctx, {
-        let app_id = ctx.require_id()?.to_string();
+    super::audit::audited!("apps", delete, request, ctx, {
+        let segments = route_segments(request.uri().path());
+
+        // Permanently remove a deleted app
+        if let ["trash", entry] = segments.as_slice() {
+            let apps_path = get_apps_directory();
+            let entry_path = resolve_trash_entry(&apps_path, entry)?;
+            if !entry_path.is_dir() {
+                return not_found(&format!("Trash entry '{}' not found", entry));
+            }
+
+            std::fs::remove_dir_all(&entry_path)
+                .map_err(|e| YetiError::Internal(format!("Failed to purge trash entry: {}", e)))?;
+            if let Err(e) = super::files::purge_app_backups(entry) {
+                yeti_log!(warn, "Failed to remove backups of trash entry '{}': {}", entry, e);
+            }
+            super::secrets::purge_app_secrets(entry).map_err(YetiError::Internal)?;
 
-        // Cannot delete self
-        if app_id == "yeti-applications" {
-            return bad_request("Cannot delete the yeti-applications app");
+            return reply().json(json!({"purged": true, "entry": entry}));
         }
 
+        let app_id = ctx.require_id()?.to_string();
+        let query = request.uri().query().unwrap_or("");
+
         let apps_path = get_apps_directory();
         let app_path = apps_path.join(&app_id);
 
@@ -393,17 +4899,24 @@ static_files:
             return not_found(&format!("Application '{}' not found", app_id));
         }
 
-        // Remove app directory
-        std::fs::remove_dir_all(&app_path)
-            .map_err(|e| YetiError::Internal(format!("Failed to remove app directory: {}", e)))?;
+        // Protected apps (this app, extensions, admin.protection entries)
+        if let Some(rejection) = protection_rejection(&app_id, "deleted", parse_query_param(query, "confirm").as_deref()) {
+            return reply().code(403).json(rejection);
+        }
+
+        // Move app directory to trash (restorable until retention expires)
+        let trash_entry = move_to_trash(&apps_path, &app_id)
+            .map_err(|e| YetiError::Internal(format!("Failed to move app to trash: {}", e)))?;
 
-        // Also remove cache directory if it exists
+        // Also remove cache directory if it exists (rebuilt on restore)
         let cache_path = get_cache_directory().join(&app_id);
         if cache_path.is_dir() {
             let _ = std::fs::remove_dir_all(&cache_path);
         }
 
-        reply().json(json!({"deleted": true, "app_id": app_id}))
+        purge_expired_trash(&apps_path);
+
+        reply().json(json!({"deleted": true, "app_id": app_id, "trash_entry": trash_entry}))
     });
 }
 
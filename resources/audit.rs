@@ -0,0 +1,368 @@
+//! Admin Audit Log Resource
+//!
+//! Records and serves AdminAuditEvent entries. The mutating handlers of the
+//! other admin resources (apps, files, keys, secrets, repos, runner) are
+//! wrapped in `audited!`, defined here, which writes one event per request.
+//!
+//! | Method | Path                         | Description                        |
+//! |--------|------------------------------|------------------------------------|
+//! | GET    | /admin/audit                 | Filtered events, newest first      |
+//! | GET    | /admin/audit/export          | Filtered events as NDJSON download |
+//!
+//! Filters (all optional): `actor`, `action`, `resource`, `outcome`, `target`
+//! (substring), `since` / `until` (RFC 3339), `offset` and `limit` (default 100,
+//! export is unlimited unless `limit` is given). The listing reports `has_more`
+//! instead of a total so a page only reads the days it needs.
+//!
+//! AdminAuditEvent is not exported; this resource is the only way to read it.
+
+use yeti_core::prelude::*;
+
+pub type Audit = AuditResource;
+
+#[derive(Default)]
+pub struct AuditResource;
+
+/// Default page size for the JSON listing
+const DEFAULT_LIMIT: usize = 100;
+
+// ── Recording ──
+//
+// Every mutating admin handler runs inside `audited!`, the only writer of
+// AdminAuditEvent records. Handlers call it by path with their resource name,
+// e.g. `super::audit::audited!("keys", post, request, ctx, { ... })`.
+//
+// Events are found through AdminAuditIndex rather than table scans: one
+// record per UTC day (`2026-01-31`) lists that day's event ids in the order
+// they were written, and the `days` record lists the days that have events.
+
+/// AdminAuditIndex record listing the days that have events
+pub(crate) const DAYS_ID: &str = "days";
+
+/// Serialises read-modify-write updates of AdminAuditIndex records
+pub(crate) static INDEX_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// AdminAuditIndex record id (UTC day) for an event
+pub(crate) fn event_day(event: &serde_json::Value) -> String {
+    event["timestamp"].as_str().unwrap_or_default().chars().take(10).collect()
+}
+
+/// Index record `id` with `entry` appended to its `entries` list
+pub(crate) fn index_append(record: Option<serde_json::Value>, id: &str, entry: &str) -> serde_json::Value {
+    let mut entries = record
+        .and_then(|r| r["entries"].as_array().cloned())
+        .unwrap_or_default();
+    if !entries.iter().any(|e| e.as_str() == Some(entry)) {
+        entries.push(json!(entry));
+    }
+    json!({"id": id, "entries": entries})
+}
+
+/// Entries of an index record, newest first
+fn index_entries(record: Option<serde_json::Value>) -> Vec<String> {
+    let mut entries: Vec<String> = record
+        .and_then(|r| r["entries"].as_array().cloned())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|e| e.as_str().map(String::from))
+        .collect();
+    entries.reverse();
+    entries
+}
+
+/// Body keys whose values are never written to the audit log
+const AUDIT_REDACTED_KEYS: &[&str] = &["password", "secret", "token", "private_key", "passphrase", "content", "value"];
+
+/// Longest string value kept in an audit summary
+const AUDIT_MAX_STRING: usize = 200;
+
+/// `text` with the userinfo (`user:token@`) removed from every `scheme://` URL in it
+fn strip_url_userinfo(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find("://") {
+        let (head, tail) = rest.split_at(pos + 3);
+        out.push_str(head);
+        let authority_end = tail
+            .find(|c: char| matches!(c, '/' | '?' | '#') || c.is_whitespace())
+            .unwrap_or(tail.len());
+        let host_start = tail[..authority_end].rfind('@').map(|i| i + 1).unwrap_or(0);
+        rest = &tail[host_start..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Copy of a request body with secrets and URL credentials redacted and long strings shortened
+fn audit_redact(value: &serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => serde_json::Value::Object(map.iter().map(|(k, v)| {
+            let lower = k.to_lowercase();
+            let redacted = if AUDIT_REDACTED_KEYS.iter().any(|s| lower.contains(s)) {
+                json!("[redacted]")
+            } else {
+                audit_redact(v)
+            };
+            (k.clone(), redacted)
+        }).collect()),
+        serde_json::Value::Array(items) => serde_json::Value::Array(items.iter().map(audit_redact).collect()),
+        serde_json::Value::String(s) => {
+            let s = strip_url_userinfo(s);
+            if s.len() > AUDIT_MAX_STRING { json!(format!("[{} chars]", s.len())) } else { json!(s) }
+        }
+        other => other.clone(),
+    }
+}
+
+/// Redacted request summary: query parameters and the JSON body (or its size)
+pub(crate) fn audit_summary(query: Option<&str>, body: &[u8]) -> serde_json::Value {
+    let query: serde_json::Map<String, serde_json::Value> = query.unwrap_or("")
+        .split('&')
+        .filter_map(|pair| pair.split_once('=').or(Some((pair, ""))))
+        .filter(|(k, _)| !k.is_empty())
+        .map(|(k, v)| (k.to_string(), json!(v)))
+        .collect();
+    let body = if body.is_empty() {
+        serde_json::Value::Null
+    } else {
+        match serde_json::from_slice::<serde_json::Value>(body) {
+            Ok(json) => audit_redact(&json),
+            Err(_) => json!(format!("[{} bytes]", body.len())),
+        }
+    };
+    audit_redact(&json!({"query": query, "body": body}))
+}
+
+/// What a request acted on: the path below the resource, else the app/path/id in the request.
+/// URL credentials are stripped.
+fn audit_target(path: &str, resource: &str, summary: &serde_json::Value) -> String {
+    strip_url_userinfo(&request_target(path, resource, summary))
+}
+
+/// Unredacted target for `audit_target`
+fn request_target(path: &str, resource: &str, summary: &serde_json::Value) -> String {
+    let tail = path.split_once(&format!("/{}", resource))
+        .map(|(_, rest)| rest.trim_matches('/'))
+        .unwrap_or("");
+    if !tail.is_empty() {
+        return tail.to_string();
+    }
+    for source in [&summary["query"], &summary["body"]] {
+        if let Some(app) = source["app"].as_str() {
+            return match source["path"].as_str() {
+                Some(p) => format!("{}:{}", app, p),
+                None => app.to_string(),
+            };
+        }
+        for key in ["id", "name", "test", "url"] {
+            if let Some(v) = source[key].as_str() {
+                return v.to_string();
+            }
+        }
+    }
+    String::new()
+}
+
+/// Outcome recorded for a response status: refusals are `rejected`, not `completed`
+fn audit_outcome(status: u16) -> &'static str {
+    match status {
+        500.. => "error",
+        400..=499 => "rejected",
+        _ => "completed",
+    }
+}
+
+/// Status code the server sends for a handler error
+fn error_status(error: &YetiError) -> u16 {
+    match error {
+        YetiError::Validation(_) => 400,
+        YetiError::Unauthorized(_) => 401,
+        YetiError::Forbidden(_) => 403,
+        YetiError::NotFound(_) => 404,
+        YetiError::Conflict(_) => 409,
+        _ => 500,
+    }
+}
+
+/// AdminAuditEvent record for a finished request
+pub(crate) fn audit_event(
+    resource: &str,
+    method: &str,
+    path: &str,
+    actor: Option<&str>,
+    summary: serde_json::Value,
+    result: &Result<Response>,
+    started: std::time::Instant,
+) -> serde_json::Value {
+    let (status, error) = match result {
+        Ok(response) => (response.status().as_u16(), None),
+        Err(e) => (error_status(e), Some(e.to_string())),
+    };
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    json!({
+        "id": format!("{}-{}", now.as_nanos(), resource),
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "actor": actor.unwrap_or("unknown"),
+        "resource": resource,
+        "action": format!("{}.{}", resource, method.to_lowercase()),
+        "method": method,
+        "path": path,
+        "target": audit_target(path, resource, &summary),
+        "summary": summary.to_string(),
+        "status": status,
+        "outcome": audit_outcome(status),
+        "error": error,
+        "durationMs": started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Wrap a handler macro (`post`, `put`, `delete`) so the request is recorded in AdminAuditEvent
+/// under `$resource`
+macro_rules! audited {
+    ($resource:expr, $method:ident, $request:ident, $ctx:ident, $body:block) => {
+        $method!($request, $ctx, {
+            let started = std::time::Instant::now();
+            let path = $request.uri().path().to_string();
+            let summary = super::audit::audit_summary($request.uri().query(), $request.body());
+
+            let result: Result<Response> = async $body.await;
+
+            let event = super::audit::audit_event(
+                $resource, &stringify!($method).to_uppercase(), &path,
+                $ctx.username(), summary, &result, started,
+            );
+            let id = event["id"].as_str().unwrap_or_default().to_string();
+            let day = super::audit::event_day(&event);
+            let recorded = match $ctx.get_table("AdminAuditEvent") {
+                Ok(table) => table.put(&id, event).await.map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            let indexed = match (recorded, $ctx.get_table("AdminAuditIndex")) {
+                (Ok(()), Ok(index)) => {
+                    let _guard = super::audit::INDEX_LOCK.lock().await;
+                    let existing = index.get_by_id(&day).await.ok().flatten();
+                    let new_day = existing.is_none();
+                    let mut outcome = index.put(&day, super::audit::index_append(existing, &day, &id)).await;
+                    if new_day && outcome.is_ok() {
+                        let days = index.get_by_id(super::audit::DAYS_ID).await.ok().flatten();
+                        outcome = index.put(super::audit::DAYS_ID, super::audit::index_append(days, super::audit::DAYS_ID, &day)).await;
+                    }
+                    outcome.map_err(|e| e.to_string())
+                }
+                (Ok(()), Err(e)) => Err(e.to_string()),
+                (Err(e), _) => Err(e),
+            };
+            if let Err(e) = indexed {
+                yeti_log!(warn, "Failed to record audit event {}: {}", id, e);
+            }
+            result
+        });
+    };
+}
+pub(crate) use audited;
+
+// ── Queries ──
+
+/// Whether an event matches the query filters
+fn matches_filters(event: &serde_json::Value, query: &str) -> bool {
+    for key in ["actor", "action", "resource", "outcome"] {
+        if let Some(expected) = parse_query_param(query, key) {
+            if event[key].as_str() != Some(expected.as_str()) {
+                return false;
+            }
+        }
+    }
+    if let Some(target) = parse_query_param(query, "target") {
+        if !event["target"].as_str().is_some_and(|t| t.contains(&target)) {
+            return false;
+        }
+    }
+
+    // RFC 3339 timestamps in UTC compare correctly as strings
+    let timestamp = event["timestamp"].as_str().unwrap_or("");
+    if parse_query_param(query, "since").is_some_and(|since| timestamp < since.as_str()) {
+        return false;
+    }
+    if parse_query_param(query, "until").is_some_and(|until| timestamp > until.as_str()) {
+        return false;
+    }
+    true
+}
+
+fn parse_usize_param(query: &str, key: &str) -> Result<Option<usize>> {
+    match parse_query_param(query, key) {
+        Some(v) => v.parse::<usize>()
+            .map(Some)
+            .map_err(|_| YetiError::Validation(format!("'{}' must be a non-negative integer, got '{}'", key, v))),
+        None => Ok(None),
+    }
+}
+
+impl Resource for AuditResource {
+    fn name(&self) -> &str {
+        "audit"
+    }
+
+    get!(request, ctx, {
+        let query = request.uri().query().unwrap_or("");
+        let export = request.uri().path().trim_end_matches('/').ends_with("/export");
+
+        let offset = parse_usize_param(query, "offset")?.unwrap_or(0);
+        let limit = parse_usize_param(query, "limit")?
+            .unwrap_or(if export { usize::MAX } else { DEFAULT_LIMIT });
+
+        // Only days inside since/until are read; events come back newest first
+        let since_day: String = parse_query_param(query, "since").unwrap_or_default().chars().take(10).collect();
+        let until_day: Option<String> = parse_query_param(query, "until").map(|u| u.chars().take(10).collect());
+        let index = ctx.get_table("AdminAuditIndex")?;
+        let table = ctx.get_table("AdminAuditEvent")?;
+        let days = index_entries(index.get_by_id(DAYS_ID).await?);
+
+        let mut page: Vec<serde_json::Value> = Vec::new();
+        let mut matched = 0usize;
+        let mut has_more = false;
+        'days: for day in days.iter()
+            .filter(|d| d.as_str() >= since_day.as_str())
+            .filter(|d| until_day.as_ref().is_none_or(|u| d.as_str() <= u.as_str()))
+        {
+            for id in index_entries(index.get_by_id(day).await?) {
+                let Some(event) = table.get_by_id(&id).await? else { continue };
+                if !matches_filters(&event, query) {
+                    continue;
+                }
+                matched += 1;
+                if matched <= offset {
+                    continue;
+                }
+                if page.len() == limit {
+                    has_more = true;
+                    break 'days;
+                }
+                page.push(event);
+            }
+        }
+
+        if export {
+            let mut ndjson = String::new();
+            for event in &page {
+                ndjson.push_str(&event.to_string());
+                ndjson.push('\n');
+            }
+            return reply()
+                .header("Content-Type", "application/x-ndjson")
+                .header("Content-Disposition", "attachment; filename=\"admin-audit.ndjson\"")
+                .body(ndjson.into_bytes());
+        }
+
+        reply().json(json!({
+            "offset": offset,
+            "limit": limit,
+            "has_more": has_more,
+            "events": page,
+        }))
+    });
+}
+
+register_resource!(AuditResource);
@@ -183,7 +183,7 @@ impl Resource for BenchmarksResource {
         }))
     });
 
-    post!(request, ctx, {
+    super::audit::audited!("runner", post, request, ctx, {
         let body = request.json_value()?;
         let test_id = body.require_str("test")?;
 
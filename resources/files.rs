@@ -5,12 +5,54 @@
 //! | Method | Path                                           | Description        |
 //! |--------|------------------------------------------------|--------------------|
 //! | GET    | /yeti-applications/files?app={id}&path=/       | List directory      |
-//! | GET    | /yeti-applications/files?app={id}&path=/f.rs   | Read file as text   |
+//! | GET    | ...&path=/&depth=3&glob=**/*.rs&gitignore=true | List a subtree      |
+//! | GET    | /yeti-applications/files?app={id}&path=/f.rs   | Read file (JSON)    |
+//! | GET    | ...&path=/logo.png&raw=true                    | Raw bytes, inline   |
+//! | GET    | ...&path=/logo.png&download=true               | Raw bytes, download |
 //! | PUT    | /yeti-applications/files                       | Update file         |
+//! | PUT    | /yeti-applications/files?check=true            | Update .rs + check  |
 //! | POST   | /yeti-applications/files                       | Create file         |
 //! | DELETE | /yeti-applications/files?app={id}&path=/file   | Delete file         |
+//! | GET    | /yeti-applications/files/search?app={id}&q=     | Search app files   |
+//! | GET    | /yeti-applications/files/backups?app={id}&path= | List file backups  |
+//! | GET    | ...files/backups?app={id}&path=&backup={bid}    | Read one backup    |
+//! | POST   | /yeti-applications/files/backups/restore        | Restore a backup   |
+//!
+//! Writes to an app's root `config.yaml` are validated before they hit disk.
+//! Deleting an app's root, `config.yaml` or `schemas/` follows the same
+//! `admin.protection` policy as deleting the app itself.
+//!
+//! Saving a `.rs` file runs a compile check of the app when `?check=true` is
+//! given or `admin.check.on_save` is set; diagnostics are returned with the write.
+//!
+//! Reads return text as-is and binary files base64-encoded (`"encoding": "base64"`).
+//! POST/PUT take JSON (`content`, optional `encoding: base64`), multipart/form-data
+//! (`app`/`path` fields plus a file part) or a raw body with `app` and `path` in the
+//! query. Uploads over `admin.files.max_upload_bytes` are refused with 413.
+//!
+//! File reads carry an `ETag` (content hash plus mtime). PUT and DELETE honour
+//! `If-Match` and answer 412 with the file's current version when it has changed.
+//!
+//! Writes go through a temp file, fsync and rename, keeping the file's permissions.
+//! The version being replaced or deleted is copied to `{root}/.admin-backups`
+//! (the newest `admin.backups.keep` per file, default 10).
+//!
+//! Listings take `depth` (default 1), `glob` (matched against the name, or the
+//! path below the listed directory when it contains `/`), `gitignore=true` (skip
+//! `.git` and anything the app's `.gitignore` files exclude), `offset` and `limit`.
+//! The walk stops one entry past the page and reports `has_more`; `total` is only
+//! known on the last page. Entries carry mtime, unix mode, symlink target and a
+//! guessed MIME type.
+//!
+//! Search takes `q`, `mode` (`literal` or `regex`), `case_sensitive`, comma-separated
+//! `include` / `exclude` globs, `context` lines and `max_results`; `app=*` searches
+//! every app. Binary files, `.gitignore`d paths and build/VCS directories are skipped.
+//!
+//! The encrypted secret store (see secrets.rs) is never listed or accessible here.
 
-use std::path::PathBuf;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use std::path::{Component, Path, PathBuf};
 use yeti_core::prelude::*;
 
 pub type Files = FilesResource;
@@ -21,6 +63,7 @@ pub struct FilesResource;
 /// Validate and resolve a file path within an app directory.
 /// Returns the canonical path if safe, or an error if path traversal is detected.
 fn resolve_safe_path(app_id: &str, rel_path: &str) -> Result<PathBuf> {
+    validate_identifier(app_id, "app")?;
     let app_path = get_root_directory().join("applications").join(app_id);
     if !app_path.is_dir() {
         return Err(YetiError::Validation(format!("Application '{}' not found", app_id)));
@@ -33,6 +76,777 @@ fn resolve_safe_path(app_id: &str, rel_path: &str) -> Result<PathBuf> {
     validate_path_within_base(&app_path, clean_path)
 }
 
+/// Secret store names (same as MASTER_KEY_FILE / STORE_DIR in secrets.rs)
+const SECRET_STORE_NAMES: &[&str] = &[".admin-secrets.key", ".admin-secrets"];
+
+/// Whether a path is, or lies inside, the encrypted secret store.
+/// These are never listed, read, written or deleted through the file browser.
+fn is_secret_store_path(path: &Path) -> bool {
+    if path.components().any(|c| matches!(c, Component::Normal(name) if SECRET_STORE_NAMES.iter().any(|s| name == *s))) {
+        return true;
+    }
+    let root = get_root_directory();
+    SECRET_STORE_NAMES.iter().any(|name| {
+        root.join(name).canonicalize().is_ok_and(|store| path.starts_with(&store))
+    })
+}
+
+/// Whether a request path points at the app's root config.yaml
+fn is_app_config(rel_path: &str) -> bool {
+    let parts: Vec<_> = Path::new(rel_path)
+        .components()
+        .filter(|c| !matches!(c, Component::RootDir | Component::CurDir))
+        .collect();
+    matches!(parts.as_slice(), [Component::Normal(name)] if *name == "config.yaml")
+}
+
+/// Whether a resolved path is the app root, its config.yaml, or inside schemas/
+fn is_protected_path(app_id: &str, safe_path: &Path) -> bool {
+    let Ok(app_root) = get_root_directory().join("applications").join(app_id).canonicalize() else {
+        return true;
+    };
+    let Ok(rel) = safe_path.strip_prefix(&app_root) else {
+        return true;
+    };
+    let parts: Vec<_> = rel.components().collect();
+    match parts.as_slice() {
+        [] => true,
+        [Component::Normal(name)] if *name == "config.yaml" => true,
+        [Component::Normal(name), ..] => *name == "schemas",
+        _ => false,
+    }
+}
+
+// ── Binary content and uploads ──
+
+/// Upload size limit when `admin.files.max_upload_bytes` is unset
+const DEFAULT_MAX_UPLOAD_BYTES: usize = 25 * 1024 * 1024;
+
+/// Largest file accepted by POST/PUT (`admin.files.max_upload_bytes` overrides)
+fn max_upload_bytes() -> usize {
+    super::apps::admin_settings()
+        .pointer("/files/max_upload_bytes")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as usize)
+        .unwrap_or(DEFAULT_MAX_UPLOAD_BYTES)
+}
+
+/// Content type for a file, guessed from its extension
+fn mime_type(path: &Path) -> &'static str {
+    let ext = path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "json" | "map" => "application/json",
+        "yaml" | "yml" => "application/yaml",
+        "toml" => "application/toml",
+        "md" => "text/markdown; charset=utf-8",
+        "txt" | "rs" | "ts" | "tsx" | "jsx" | "graphql" | "sh" | "lock" => "text/plain; charset=utf-8",
+        "csv" => "text/csv; charset=utf-8",
+        "xml" => "application/xml",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "wasm" => "application/wasm",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" | "tgz" => "application/gzip",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        _ => "application/octet-stream",
+    }
+}
+
+/// `Content-Disposition` value; non-ASCII names are sent as RFC 5987 `filename*`
+fn content_disposition(disposition: &str, file_name: &str) -> String {
+    let fallback: String = file_name.chars()
+        .map(|c| if c.is_ascii_graphic() && c != '"' && c != '\\' || c == ' ' { c } else { '_' })
+        .collect();
+    if fallback == file_name {
+        return format!("{}; filename=\"{}\"", disposition, file_name);
+    }
+    let encoded: String = file_name.bytes()
+        .map(|b| if b.is_ascii_alphanumeric() || b"-._~".contains(&b) { (b as char).to_string() } else { format!("%{:02X}", b) })
+        .collect();
+    format!("{}; filename=\"{}\"; filename*=UTF-8''{}", disposition, fallback, encoded)
+}
+
+/// Target and decoded content of a POST/PUT
+struct Upload {
+    app_id: String,
+    rel_path: String,
+    content: Vec<u8>,
+}
+
+/// One part of a multipart/form-data body
+struct MultipartPart {
+    name: String,
+    filename: Option<String>,
+    data: Vec<u8>,
+}
+
+/// Parameter value from a header such as `form-data; name="file"; filename="a.png"`
+fn header_param(header: &str, key: &str) -> Option<String> {
+    header.split(';').skip(1).find_map(|param| {
+        let (k, v) = param.trim().split_once('=')?;
+        k.trim().eq_ignore_ascii_case(key).then(|| v.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Split a multipart/form-data body into its parts
+fn parse_multipart(body: &[u8], boundary: &str) -> Result<Vec<MultipartPart>> {
+    let malformed = || YetiError::Validation("Malformed multipart body".to_string());
+    let delimiter = format!("--{}", boundary).into_bytes();
+    let find = |haystack: &[u8], needle: &[u8], from: usize| -> Option<usize> {
+        haystack.get(from..)?.windows(needle.len()).position(|w| w == needle).map(|p| p + from)
+    };
+
+    let mut parts = Vec::new();
+    let mut pos = find(body, &delimiter, 0).ok_or_else(malformed)? + delimiter.len();
+    loop {
+        // `--` after a delimiter closes the body
+        if body[pos..].starts_with(b"--") {
+            return Ok(parts);
+        }
+        let headers_start = find(body, b"\r\n", pos).ok_or_else(malformed)? + 2;
+        let headers_end = find(body, b"\r\n\r\n", headers_start).ok_or_else(malformed)?;
+        let next = find(body, &delimiter, headers_end).ok_or_else(malformed)?;
+        // Part data ends with the CRLF that precedes the next delimiter
+        let data_end = next.checked_sub(2).filter(|&end| end >= headers_end + 4).ok_or_else(malformed)?;
+
+        let headers = String::from_utf8_lossy(&body[headers_start..headers_end]);
+        let disposition = headers.lines()
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.trim().eq_ignore_ascii_case("content-disposition").then(|| value.trim().to_string())
+            })
+            .ok_or_else(malformed)?;
+        parts.push(MultipartPart {
+            name: header_param(&disposition, "name").unwrap_or_default(),
+            filename: header_param(&disposition, "filename"),
+            data: body[headers_end + 4..data_end].to_vec(),
+        });
+        pos = next + delimiter.len();
+    }
+}
+
+/// Slack for JSON/multipart framing around an upload's content
+const UPLOAD_FRAMING_BYTES: usize = 64 * 1024;
+
+/// 413 body for an upload of `size` bytes
+fn upload_rejection(size: usize, limit: usize) -> serde_json::Value {
+    json!({
+        "error": format!("Upload is {} bytes, the upload limit is {} bytes", size, limit),
+        "limit": limit,
+    })
+}
+
+/// Decode a POST/PUT body. JSON bodies carry `app`, `path` and `content` (with
+/// `encoding: base64` for binary data); multipart bodies carry `app`/`path` fields
+/// and a file part; any other content type is the raw file, with `app` and `path`
+/// in the query. A multipart `path` that ends in `/` gets the uploaded file name.
+///
+/// Uploads over `max_upload_bytes` come back as `Err` with the 413 body. The
+/// declared and actual body sizes are checked before anything is parsed, and
+/// base64 content before it is decoded.
+fn parse_upload(
+    content_type: &str,
+    content_length: Option<usize>,
+    query: &str,
+    body: &[u8],
+) -> Result<std::result::Result<Upload, serde_json::Value>> {
+    let limit = max_upload_bytes();
+    let media_type = content_type.split(';').next().unwrap_or("").trim().to_lowercase();
+    let is_json = media_type.is_empty() || media_type == "application/json";
+
+    // JSON string escaping or base64 can grow the content; multipart adds framing
+    let body_limit = if is_json {
+        limit.saturating_mul(2).saturating_add(UPLOAD_FRAMING_BYTES)
+    } else if media_type == "multipart/form-data" {
+        limit.saturating_add(UPLOAD_FRAMING_BYTES)
+    } else {
+        limit
+    };
+    let declared = content_length.unwrap_or(0).max(body.len());
+    if declared > body_limit {
+        return Ok(Err(upload_rejection(declared, limit)));
+    }
+
+    if is_json {
+        let json: serde_json::Value = serde_json::from_slice(body)
+            .map_err(|e| YetiError::Validation(format!("Invalid JSON body: {}", e)))?;
+        let app_id = json.require_str("app")?;
+        let rel_path = json.require_str("path")?;
+        let content = json.require_str("content")?;
+        let content = match json.get("encoding").and_then(|v| v.as_str()) {
+            None | Some("utf-8") | Some("utf8") => content.into_bytes(),
+            Some("base64") => {
+                let decoded_size = content.trim().len() / 4 * 3;
+                if decoded_size > limit {
+                    return Ok(Err(upload_rejection(decoded_size, limit)));
+                }
+                BASE64.decode(content.trim())
+                    .map_err(|e| YetiError::Validation(format!("'content' is not valid base64: {}", e)))?
+            }
+            Some(other) => return Err(YetiError::Validation(format!("Unknown encoding '{}', use 'utf-8' or 'base64'", other))),
+        };
+        if content.len() > limit {
+            return Ok(Err(upload_rejection(content.len(), limit)));
+        }
+        return Ok(Ok(Upload { app_id, rel_path, content }));
+    }
+
+    if media_type == "multipart/form-data" {
+        let boundary = header_param(content_type, "boundary")
+            .ok_or_else(|| YetiError::Validation("multipart/form-data requires a boundary".to_string()))?;
+        let parts = parse_multipart(body, &boundary)?;
+        let field = |name: &str| parts.iter()
+            .find(|p| p.name == name && p.filename.is_none())
+            .map(|p| String::from_utf8_lossy(&p.data).to_string())
+            .or_else(|| parse_query_param(query, name));
+        let file = parts.iter()
+            .find(|p| p.filename.is_some())
+            .ok_or_else(|| YetiError::Validation("Multipart body has no file part".to_string()))?;
+
+        let app_id = field("app").ok_or_else(|| YetiError::Validation("Missing 'app'".to_string()))?;
+        let mut rel_path = field("path").unwrap_or_else(|| "/".to_string());
+        if rel_path.ends_with('/') {
+            let file_name = file.filename.as_deref()
+                .and_then(|f| Path::new(f).file_name())
+                .map(|f| f.to_string_lossy().to_string())
+                .ok_or_else(|| YetiError::Validation("Uploaded file has no name; give a full 'path'".to_string()))?;
+            rel_path.push_str(&file_name);
+        }
+        if file.data.len() > limit {
+            return Ok(Err(upload_rejection(file.data.len(), limit)));
+        }
+        return Ok(Ok(Upload { app_id, rel_path, content: file.data.clone() }));
+    }
+
+    Ok(Ok(Upload {
+        app_id: parse_required_query_param(query, "app")?,
+        rel_path: parse_required_query_param(query, "path")?,
+        content: body.to_vec(),
+    }))
+}
+
+// ── Atomic writes and backups ──
+//
+// Every admin write to an app file (here and in apps.rs) goes through these
+// helpers: files are replaced through a temp file, fsync and rename, and the
+// previous version is kept in `{root}/.admin-backups/{app_id}`. The backups
+// follow the app when it is renamed, trashed, restored or purged.
+
+/// Backup area, relative to the root directory (outside every app)
+const BACKUP_DIR: &str = ".admin-backups";
+
+/// Versions kept per file when `admin.backups.keep` is unset (0 disables backups)
+const DEFAULT_BACKUP_KEEP: usize = 10;
+
+fn backup_keep() -> usize {
+    super::apps::admin_settings()
+        .pointer("/backups/keep")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as usize)
+        .unwrap_or(DEFAULT_BACKUP_KEEP)
+}
+
+/// Replace a file without ever leaving it half-written: write a temp file in the
+/// same directory, fsync it and rename it over the target. The target's
+/// permissions are kept, and symlinks are written through.
+pub(crate) fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let dir = path.parent().ok_or_else(|| std::io::Error::other("path has no parent directory"))?;
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let tmp = dir.join(format!(".{}.{}-{}.tmp", file_name, std::process::id(), nanos));
+    let permissions = std::fs::metadata(&path).ok().map(|m| m.permissions());
+
+    let result = (|| {
+        let mut file = std::fs::File::create(&tmp)?;
+        file.write_all(content)?;
+        if let Some(permissions) = permissions {
+            file.set_permissions(permissions)?;
+        }
+        file.sync_all()?;
+        std::fs::rename(&tmp, &path)?;
+        // Persist the rename itself
+        #[cfg(unix)]
+        std::fs::File::open(dir)?.sync_all()?;
+        Ok(())
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Directory holding the backups of one file; the app-relative path is flattened
+/// into a single name (`resources/a.rs` -> `resources%2Fa.rs`)
+fn backup_dir(app_id: &str, rel_path: &str) -> PathBuf {
+    let key = rel_path.trim_start_matches('/').replace('%', "%25").replace('/', "%2F");
+    get_root_directory().join(BACKUP_DIR).join(app_id).join(key)
+}
+
+/// Copy the current version of a file into the backup area and prune old versions.
+/// Returns the backup id, or None when backups are disabled or there is no file.
+pub(crate) fn backup_file(app_id: &str, rel_path: &str, path: &Path) -> std::result::Result<Option<String>, String> {
+    let keep = backup_keep();
+    if keep == 0 || !path.is_file() {
+        return Ok(None);
+    }
+    let dir = backup_dir(app_id, rel_path);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+    let id = chrono::Utc::now().format("%Y%m%dT%H%M%S%.6fZ").to_string();
+    std::fs::copy(path, dir.join(&id))
+        .map_err(|e| format!("Failed to back up {}: {}", rel_path, e))?;
+
+    // Ids sort chronologically; drop all but the newest `keep`
+    let mut ids: Vec<String> = std::fs::read_dir(&dir)
+        .map(|entries| entries.flatten().map(|e| e.file_name().to_string_lossy().to_string()).collect())
+        .unwrap_or_default();
+    ids.sort();
+    for old in ids.iter().take(ids.len().saturating_sub(keep)) {
+        let _ = std::fs::remove_file(dir.join(old));
+    }
+    Ok(Some(id))
+}
+
+/// Backups of a file, newest first
+fn list_backups(app_id: &str, rel_path: &str) -> Vec<serde_json::Value> {
+    let dir = backup_dir(app_id, rel_path);
+    let mut backups: Vec<serde_json::Value> = std::fs::read_dir(&dir)
+        .map(|entries| entries.flatten()
+            .filter_map(|entry| {
+                let meta = entry.metadata().ok().filter(|m| m.is_file())?;
+                let created = meta.modified().ok()
+                    .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339());
+                Some(json!({
+                    "id": entry.file_name().to_string_lossy(),
+                    "size": meta.len(),
+                    "created": created,
+                }))
+            })
+            .collect())
+        .unwrap_or_default();
+    backups.sort_by(|a, b| b["id"].as_str().cmp(&a["id"].as_str()));
+    backups
+}
+
+/// Path of one backup, if the id names an existing backup of the file
+fn backup_path(app_id: &str, rel_path: &str, id: &str) -> Option<PathBuf> {
+    if id.is_empty() || id.contains(['/', '\\']) || id.starts_with('.') {
+        return None;
+    }
+    Some(backup_dir(app_id, rel_path).join(id)).filter(|p| p.is_file())
+}
+
+/// Backups of deleted apps, kept per trash entry until it is purged
+const BACKUP_TRASH_DIR: &str = ".trash";
+
+/// Move a backup area (`{app_id}` or `.trash/{entry}`) to a new key, replacing
+/// anything left there
+fn move_backup_area(from: &Path, to: &Path) -> std::io::Result<()> {
+    if !from.is_dir() {
+        return Ok(());
+    }
+    if to.exists() {
+        std::fs::remove_dir_all(to)?;
+    }
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::rename(from, to)
+}
+
+/// Re-key an app's backups after a rename
+pub(crate) fn rename_app_backups(old_id: &str, new_id: &str) -> std::io::Result<()> {
+    let root = get_root_directory().join(BACKUP_DIR);
+    move_backup_area(&root.join(old_id), &root.join(new_id))
+}
+
+/// Park an app's backups with its trash entry
+pub(crate) fn trash_app_backups(app_id: &str, entry: &str) -> std::io::Result<()> {
+    let root = get_root_directory().join(BACKUP_DIR);
+    move_backup_area(&root.join(app_id), &root.join(BACKUP_TRASH_DIR).join(entry))
+}
+
+/// Give a restored trash entry its backups back, under the id it was restored as
+pub(crate) fn restore_app_backups(entry: &str, app_id: &str) -> std::io::Result<()> {
+    let root = get_root_directory().join(BACKUP_DIR);
+    move_backup_area(&root.join(BACKUP_TRASH_DIR).join(entry), &root.join(app_id))
+}
+
+/// Drop the backups of a purged trash entry
+pub(crate) fn purge_app_backups(entry: &str) -> std::io::Result<()> {
+    let dir = get_root_directory().join(BACKUP_DIR).join(BACKUP_TRASH_DIR).join(entry);
+    if dir.is_dir() {
+        std::fs::remove_dir_all(dir)?;
+    }
+    Ok(())
+}
+
+/// Path of a resolved file relative to its app root, e.g. `resources/a.rs`; used
+/// as the backup key (falls back to the request path if it can't be resolved)
+fn app_relative_path(app_id: &str, rel_path: &str, safe_path: &Path) -> String {
+    get_root_directory().join("applications").join(app_id).canonicalize().ok()
+        .and_then(|root| safe_path.strip_prefix(&root).ok().map(|rel| rel.to_string_lossy().to_string()))
+        .unwrap_or_else(|| rel_path.trim_start_matches('/').to_string())
+}
+
+// ── Tree listing ──
+
+/// Deepest `depth` accepted by directory listings
+const MAX_LIST_DEPTH: usize = 32;
+
+/// Entries visited by one listing before it stops and reports `truncated`
+const MAX_WALK_ENTRIES: usize = 50_000;
+
+/// Default page size of directory listings
+const DEFAULT_LIST_LIMIT: usize = 1000;
+
+/// A listing or search glob. Patterns with a `/` match the path, others just the
+/// name; `*` never crosses a `/` and `**` spans any number of directories.
+struct EntryGlob {
+    matcher: globset::GlobMatcher,
+    whole_path: bool,
+}
+
+impl EntryGlob {
+    fn new(pattern: &str) -> Result<Self> {
+        let trimmed = pattern.trim_start_matches('/');
+        let matcher = globset::GlobBuilder::new(trimmed)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| YetiError::Validation(format!("Invalid glob '{}': {}", pattern, e)))?
+            .compile_matcher();
+        Ok(EntryGlob { matcher, whole_path: trimmed.contains('/') })
+    }
+
+    fn matches(&self, rel_path: &str) -> bool {
+        if self.whole_path {
+            self.matcher.is_match(rel_path)
+        } else {
+            self.matcher.is_match(rel_path.rsplit('/').next().unwrap_or(rel_path))
+        }
+    }
+}
+
+/// Options for `walk_directory`
+struct WalkOptions<'a> {
+    /// App-relative directory to list (`""` for the app root)
+    start: &'a str,
+    depth: usize,
+    glob: Option<&'a EntryGlob>,
+    gitignore: bool,
+    /// Directory names that are never listed or entered
+    skip_dirs: &'a [&'a str],
+}
+
+/// Listing entry: name, path, type, size, mtime, mode, symlink target and MIME type
+fn list_entry(path: &Path, rel_path: &str, depth: usize) -> serde_json::Value {
+    let link_meta = std::fs::symlink_metadata(path).ok();
+    let meta = std::fs::metadata(path).ok().or_else(|| link_meta.clone());
+    let is_dir = meta.as_ref().is_some_and(|m| m.is_dir());
+    let symlink_target = link_meta.as_ref()
+        .filter(|m| m.file_type().is_symlink())
+        .and_then(|_| std::fs::read_link(path).ok())
+        .map(|target| target.to_string_lossy().to_string());
+    let modified = meta.as_ref()
+        .and_then(|m| m.modified().ok())
+        .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339());
+    #[cfg(unix)]
+    let mode = {
+        use std::os::unix::fs::PermissionsExt;
+        meta.as_ref().map(|m| format!("{:04o}", m.permissions().mode() & 0o7777))
+    };
+    #[cfg(not(unix))]
+    let mode: Option<String> = None;
+
+    json!({
+        "name": path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        "path": rel_path,
+        "type": if is_dir { "directory" } else { "file" },
+        "size": meta.as_ref().map(|m| m.len()).unwrap_or(0),
+        "depth": depth,
+        "modified": modified,
+        "mode": mode,
+        "symlink_target": symlink_target,
+        "mime_type": if is_dir { None } else { Some(mime_type(path)) },
+    })
+}
+
+/// Walk `options.start` down to `options.depth` levels, directories first then
+/// by name at each level, children straight after their directory. Symlinked
+/// directories are visited but not entered. Entry paths are relative to the app
+/// root and globs match below the start. With `gitignore`, `.git` is skipped and
+/// the `.gitignore` files from the app root down apply (nothing above the app).
+///
+/// `visit` gets each matching entry's path, app-relative path and depth, and
+/// returns false to stop. Returns true if the walk hit `MAX_WALK_ENTRIES`.
+fn walk_directory(app_root: &Path, options: &WalkOptions, mut visit: impl FnMut(&Path, &str, usize) -> bool) -> bool {
+    // The walk starts at the app root so every .gitignore on the way down is read;
+    // directories off the path to the start are pruned.
+    let start = app_root.join(options.start);
+    let start_depth = Path::new(options.start).components().count();
+    let skip_dirs: Vec<String> = options.skip_dirs.iter().map(|d| d.to_string()).collect();
+    let gitignore = options.gitignore;
+    let filter_start = start.clone();
+
+    let mut builder = ignore::WalkBuilder::new(app_root);
+    builder
+        .standard_filters(false)
+        .git_ignore(gitignore)
+        .require_git(false)
+        .follow_links(false)
+        .max_depth(Some(start_depth + options.depth))
+        .sort_by_file_path(|a, b| {
+            let is_dir = |p: &Path| p.symlink_metadata().is_ok_and(|m| m.is_dir());
+            is_dir(b).cmp(&is_dir(a)).then_with(|| a.file_name().cmp(&b.file_name()))
+        })
+        .filter_entry(move |entry| {
+            let path = entry.path();
+            if !path.starts_with(&filter_start) {
+                return filter_start.starts_with(path);
+            }
+            let name = entry.file_name().to_string_lossy();
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            let skipped = (gitignore && name == ".git") || (is_dir && skip_dirs.iter().any(|d| *d == name));
+            !skipped && !is_secret_store_path(path)
+        });
+
+    let mut visited = 0;
+    for entry in builder.build().flatten() {
+        let path = entry.path();
+        let Ok(below_start) = path.strip_prefix(&start) else { continue };
+        if below_start.as_os_str().is_empty() {
+            continue;
+        }
+        visited += 1;
+        if visited > MAX_WALK_ENTRIES {
+            return true;
+        }
+        let below_start = below_start.to_string_lossy();
+        if options.glob.is_some_and(|g| !g.matches(&below_start)) {
+            continue;
+        }
+        let rel_path = match options.start {
+            "" => below_start.to_string(),
+            start => format!("{}/{}", start, below_start),
+        };
+        if !visit(path, &rel_path, entry.depth() - start_depth) {
+            break;
+        }
+    }
+    false
+}
+
+fn parse_usize_param(query: &str, key: &str) -> Result<Option<usize>> {
+    match parse_query_param(query, key) {
+        Some(v) => v.parse::<usize>()
+            .map(Some)
+            .map_err(|_| YetiError::Validation(format!("'{}' must be a non-negative integer, got '{}'", key, v))),
+        None => Ok(None),
+    }
+}
+
+// ── Search ──
+
+/// Directories search never enters, ignored or not (same as ARTIFACT_DIRS in apps.rs)
+const SEARCH_SKIP_DIRS: &[&str] = &["node_modules", ".git", "target"];
+
+/// Files larger than this are not searched
+const MAX_SEARCH_FILE_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Default and largest `max_results`
+const DEFAULT_SEARCH_RESULTS: usize = 200;
+const MAX_SEARCH_RESULTS: usize = 5000;
+
+/// Largest `context`
+const MAX_SEARCH_CONTEXT: usize = 10;
+
+/// Longest line text returned with a match
+const MAX_SEARCH_LINE_CHARS: usize = 500;
+
+/// Parsed search parameters
+struct SearchOptions {
+    matcher: regex::Regex,
+    include: Vec<EntryGlob>,
+    exclude: Vec<EntryGlob>,
+    context: usize,
+    max_results: usize,
+}
+
+/// Comma-separated glob list from a query parameter
+fn glob_list(query: &str, key: &str) -> Result<Vec<EntryGlob>> {
+    parse_query_param(query, key)
+        .map(|v| v.split(',').map(str::trim).filter(|g| !g.is_empty()).map(EntryGlob::new).collect())
+        .unwrap_or_else(|| Ok(Vec::new()))
+}
+
+/// A line shortened to `MAX_SEARCH_LINE_CHARS`
+fn clip_line(line: &str) -> String {
+    match line.char_indices().nth(MAX_SEARCH_LINE_CHARS) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+/// Search one file (app-relative `path`), appending matches until `max_results`
+/// is reached. Returns false if the file was skipped (binary, too large, outside the app).
+fn search_file(app_id: &str, path: &str, options: &SearchOptions, matches: &mut Vec<serde_json::Value>) -> bool {
+    // Symlinked files are only followed when they stay inside the app
+    let Ok(file_path) = resolve_safe_path(app_id, path) else { return false };
+    if is_secret_store_path(&file_path) || !file_path.metadata().is_ok_and(|m| m.is_file() && m.len() <= MAX_SEARCH_FILE_BYTES) {
+        return false;
+    }
+    let Ok(bytes) = std::fs::read(&file_path) else { return false };
+    // Binary files: a NUL byte in the first 8 KiB, or not UTF-8
+    if bytes.iter().take(8192).any(|&b| b == 0) {
+        return false;
+    }
+    let Ok(text) = String::from_utf8(bytes) else { return false };
+
+    let lines: Vec<&str> = text.lines().collect();
+    for (index, line) in lines.iter().enumerate() {
+        let Some(found) = options.matcher.find(line) else { continue };
+        let before = index.saturating_sub(options.context);
+        let after = (index + 1 + options.context).min(lines.len());
+        matches.push(json!({
+            "app": app_id,
+            "path": path,
+            "line": index + 1,
+            "column": line[..found.start()].chars().count() + 1,
+            "text": clip_line(line),
+            "match": clip_line(found.as_str()),
+            "before": lines[before..index].iter().map(|l| clip_line(l)).collect::<Vec<_>>(),
+            "after": lines[index + 1..after].iter().map(|l| clip_line(l)).collect::<Vec<_>>(),
+        }));
+        if matches.len() >= options.max_results {
+            break;
+        }
+    }
+    true
+}
+
+/// Search the text files below `rel_path` in one app, appending matches until
+/// `max_results` is reached. Files are filtered and searched as the walk finds
+/// them. Returns the number of files searched and whether the walk was cut short.
+fn search_app(
+    app_id: &str,
+    rel_path: &str,
+    options: &SearchOptions,
+    matches: &mut Vec<serde_json::Value>,
+) -> Result<(usize, bool)> {
+    let safe_path = resolve_safe_path(app_id, rel_path)?;
+    let app_root = get_root_directory().join("applications").join(app_id).canonicalize()
+        .map_err(|e| YetiError::Internal(format!("Cannot resolve path: {}", e)))?;
+    let start = app_relative_path(app_id, rel_path, &safe_path);
+
+    if !safe_path.is_dir() {
+        let searched = search_file(app_id, &format!("/{}", start), options, matches);
+        return Ok((usize::from(searched), false));
+    }
+
+    let walk = WalkOptions {
+        start: &start,
+        depth: MAX_LIST_DEPTH,
+        glob: None,
+        gitignore: true,
+        skip_dirs: SEARCH_SKIP_DIRS,
+    };
+    let mut searched = 0;
+    let truncated = walk_directory(&app_root, &walk, |path, relative, _| {
+        let included = options.include.is_empty() || options.include.iter().any(|g| g.matches(relative));
+        if included
+            && !options.exclude.iter().any(|g| g.matches(relative))
+            && !path.is_dir()
+            && search_file(app_id, &format!("/{}", relative), options, matches)
+        {
+            searched += 1;
+        }
+        matches.len() < options.max_results
+    });
+    Ok((searched, truncated))
+}
+
+// ── ETags ──
+//
+// A file's ETag is a hash of its content plus its mtime. PUT and DELETE honour
+// `If-Match` so concurrent editors can't silently overwrite each other.
+
+/// Serialises admin writes to app files (this resource and apps.rs), so an
+/// If-Match check, the backup and the write or delete it guards never interleave
+pub(crate) static FILE_WRITE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Quoted ETag for file content and its metadata
+fn etag_for(content: &[u8], meta: &std::fs::Metadata) -> String {
+    use sha2::Digest;
+    let digest = sha2::Sha256::digest(content);
+    let hash: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    let mtime = meta.modified().ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    format!("\"{}-{:x}\"", hash, mtime)
+}
+
+/// ETag, size and modification time of a file, or None if it isn't a readable file
+fn file_version(path: &Path) -> Option<serde_json::Value> {
+    let meta = std::fs::metadata(path).ok().filter(|m| m.is_file())?;
+    let content = std::fs::read(path).ok()?;
+    let modified = meta.modified().ok()
+        .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339());
+    Some(json!({
+        "etag": etag_for(&content, &meta),
+        "size": meta.len(),
+        "modified": modified,
+    }))
+}
+
+/// Whether an `If-Match` header value matches. `*` matches anything that exists
+/// (files and directories); tags use strong comparison (RFC 9110 §13.1.1), so a
+/// weak `W/` tag never matches.
+fn if_match_satisfied(if_match: &str, exists: bool, current: Option<&str>) -> bool {
+    if_match.split(',')
+        .map(str::trim)
+        .any(|tag| (tag == "*" && exists) || (!tag.starts_with("W/") && Some(tag) == current))
+}
+
+/// 412 body when `If-Match` doesn't match the file at `safe_path`, carrying its current version
+fn if_match_rejection(if_match: Option<&str>, app_id: &str, rel_path: &str, safe_path: &Path) -> Option<serde_json::Value> {
+    let if_match = if_match?;
+    let current = file_version(safe_path);
+    let exists = safe_path.symlink_metadata().is_ok();
+    if if_match_satisfied(if_match, exists, current.as_ref().and_then(|v| v["etag"].as_str())) {
+        return None;
+    }
+    Some(json!({
+        "error": format!("'{}' in '{}' has changed since it was read", rel_path, app_id),
+        "app": app_id,
+        "path": rel_path,
+        "current": current,
+    }))
+}
+
 impl Resource for FilesResource {
     fn name(&self) -> &str {
         "files"
@@ -40,45 +854,184 @@ impl Resource for FilesResource {
 
     get!(request, _ctx, {
         let query = request.uri().query().unwrap_or("");
+
+        // Text search in one app (`app=*` for all of them)
+        if request.uri().path().trim_end_matches('/').ends_with("/search") {
+            let app_param = parse_required_query_param(query, "app")?;
+            let q = parse_required_query_param(query, "q")?;
+            if q.is_empty() {
+                return bad_request("'q' must not be empty");
+            }
+            let rel_path = parse_query_param(query, "path").unwrap_or_else(|| "/".to_string());
+            let mode = parse_query_param(query, "mode").unwrap_or_else(|| "literal".to_string());
+            let pattern = match mode.as_str() {
+                "literal" => regex::escape(&q),
+                "regex" => q.clone(),
+                other => return bad_request(&format!("Unknown mode '{}', use 'literal' or 'regex'", other)),
+            };
+            let case_sensitive = parse_query_param(query, "case_sensitive").as_deref() == Some("true");
+            let matcher = regex::RegexBuilder::new(&pattern)
+                .case_insensitive(!case_sensitive)
+                .size_limit(1 << 20)
+                .build()
+                .map_err(|e| YetiError::Validation(format!("Invalid regex: {}", e)))?;
+            let options = SearchOptions {
+                matcher,
+                include: glob_list(query, "include")?,
+                exclude: glob_list(query, "exclude")?,
+                context: parse_usize_param(query, "context")?.unwrap_or(0).min(MAX_SEARCH_CONTEXT),
+                max_results: parse_usize_param(query, "max_results")?
+                    .unwrap_or(DEFAULT_SEARCH_RESULTS)
+                    .clamp(1, MAX_SEARCH_RESULTS),
+            };
+
+            let app_ids: Vec<String> = if app_param == "*" {
+                let mut ids: Vec<String> = std::fs::read_dir(get_root_directory().join("applications"))
+                    .map(|entries| entries.flatten()
+                        .filter(|e| e.path().is_dir())
+                        .map(|e| e.file_name().to_string_lossy().to_string())
+                        .filter(|id| !id.starts_with('.') && validate_identifier(id, "app").is_ok())
+                        .collect())
+                    .unwrap_or_default();
+                ids.sort();
+                ids
+            } else {
+                vec![app_param.clone()]
+            };
+
+            // Reading every file of every app is blocking work; keep it off the executor
+            let max_results = options.max_results;
+            let apps_searched = app_ids.len();
+            let scan = {
+                let (app_param, rel_path) = (app_param.clone(), rel_path.clone());
+                tokio::task::spawn_blocking(move || -> Result<(Vec<serde_json::Value>, usize, bool)> {
+                    let mut matches = Vec::new();
+                    let mut files_searched = 0;
+                    let mut walk_truncated = false;
+                    for app_id in &app_ids {
+                        if matches.len() >= options.max_results {
+                            break;
+                        }
+                        match search_app(app_id, &rel_path, &options, &mut matches) {
+                            Ok((count, truncated)) => {
+                                files_searched += count;
+                                walk_truncated |= truncated;
+                            }
+                            // A path that only exists in some apps doesn't fail an `app=*` search
+                            Err(_) if app_param == "*" => {}
+                            Err(e) => return Err(e),
+                        }
+                    }
+                    Ok((matches, files_searched, walk_truncated))
+                })
+            };
+            let (matches, files_searched, walk_truncated) = scan.await
+                .map_err(|e| YetiError::Internal(format!("Search failed: {}", e)))??;
+
+            return reply().json(json!({
+                "app": app_param,
+                "path": rel_path,
+                "q": q,
+                "mode": mode,
+                "case_sensitive": case_sensitive,
+                "apps_searched": apps_searched,
+                "files_searched": files_searched,
+                "truncated": matches.len() >= max_results || walk_truncated,
+                "walk_truncated": walk_truncated,
+                "matches": matches,
+            }));
+        }
+
         let app_id = parse_required_query_param(query, "app")?;
         let rel_path = parse_query_param(query, "path")
             .unwrap_or_else(|| "/".to_string());
 
         let safe_path = resolve_safe_path(&app_id, &rel_path)?;
+        if is_secret_store_path(&safe_path) {
+            return not_found(&format!("Path '{}' not found in app '{}'", rel_path, app_id));
+        }
 
-        // Directory listing
-        if safe_path.is_dir() {
-            let entries = std::fs::read_dir(&safe_path)
-                .map_err(|e| YetiError::Internal(format!("Cannot read directory: {}", e)))?;
-
-            let mut items: Vec<serde_json::Value> = Vec::new();
-            for entry in entries.flatten() {
-                let meta = entry.metadata().ok();
-                let name = entry.file_name().to_string_lossy().to_string();
-                let is_dir = meta.as_ref().map_or(false, |m| m.is_dir());
-                let size = meta.as_ref().map_or(0, |m| m.len());
-
-                items.push(json!({
-                    "name": name,
-                    "type": if is_dir { "directory" } else { "file" },
-                    "size": size,
+        // Previous versions of a file, or one version's content with `backup={id}`
+        if request.uri().path().trim_end_matches('/').ends_with("/backups") {
+            let key = app_relative_path(&app_id, &rel_path, &safe_path);
+            let Some(id) = parse_query_param(query, "backup") else {
+                return reply().json(json!({
+                    "app": app_id,
+                    "path": rel_path,
+                    "backups": list_backups(&app_id, &key),
                 }));
+            };
+            let Some(path) = backup_path(&app_id, &key, &id) else {
+                return not_found(&format!("Backup '{}' of '{}' not found", id, rel_path));
+            };
+            let content = std::fs::read(&path)
+                .map_err(|e| YetiError::Internal(format!("Cannot read backup: {}", e)))?;
+            let size = content.len();
+            let (content, encoding) = match String::from_utf8(content) {
+                Ok(text) => (text, "utf-8"),
+                Err(e) => (BASE64.encode(e.into_bytes()), "base64"),
+            };
+            return reply().json(json!({
+                "app": app_id,
+                "path": rel_path,
+                "backup": id,
+                "content": content,
+                "encoding": encoding,
+                "size": size,
+            }));
+        }
+
+        // Directory listing, optionally recursive, filtered and paginated
+        if safe_path.is_dir() {
+            let depth = parse_usize_param(query, "depth")?.unwrap_or(1);
+            if depth == 0 || depth > MAX_LIST_DEPTH {
+                return bad_request(&format!("'depth' must be between 1 and {}", MAX_LIST_DEPTH));
             }
+            let glob = parse_query_param(query, "glob")
+                .filter(|g| !g.is_empty())
+                .map(|g| EntryGlob::new(&g))
+                .transpose()?;
+            let app_root = get_root_directory().join("applications").join(&app_id).canonicalize()
+                .map_err(|e| YetiError::Internal(format!("Cannot resolve path: {}", e)))?;
+            let start = app_relative_path(&app_id, &rel_path, &safe_path);
+            let options = WalkOptions {
+                start: &start,
+                depth,
+                glob: glob.as_ref(),
+                gitignore: parse_query_param(query, "gitignore").as_deref() == Some("true"),
+                skip_dirs: &[],
+            };
+            let offset = parse_usize_param(query, "offset")?.unwrap_or(0);
+            let limit = parse_usize_param(query, "limit")?.unwrap_or(DEFAULT_LIST_LIMIT);
 
-            items.sort_by(|a, b| {
-                let a_type = a["type"].as_str().unwrap_or("");
-                let b_type = b["type"].as_str().unwrap_or("");
-                let a_name = a["name"].as_str().unwrap_or("");
-                let b_name = b["name"].as_str().unwrap_or("");
-                // Directories first, then alphabetical
-                b_type.cmp(a_type).then(a_name.cmp(b_name))
+            // Stop one entry past the page; only page entries get their metadata read
+            let mut seen = 0;
+            let mut page = Vec::new();
+            let truncated = walk_directory(&app_root, &options, |path, relative, depth| {
+                if seen >= offset && page.len() < limit {
+                    page.push(list_entry(path, &format!("/{}", relative), depth));
+                }
+                seen += 1;
+                seen <= offset.saturating_add(limit)
             });
+            let has_more = seen > offset.saturating_add(limit);
+            // The total is only known when the walk reached the end
+            let total = (!has_more && !truncated).then_some(seen);
 
-            return reply().json(json!({
+            let mut response = reply();
+            if let Some(total) = total {
+                response = response.header("X-Total-Count", &total.to_string());
+            }
+            return response.json(json!({
                 "app": app_id,
                 "path": rel_path,
                 "type": "directory",
-                "entries": items,
+                "entries": page,
+                "total": total,
+                "offset": offset,
+                "limit": limit,
+                "has_more": has_more,
+                "truncated": truncated,
             }));
         }
 
@@ -86,95 +1039,261 @@ impl Resource for FilesResource {
         if safe_path.is_file() {
             let content = std::fs::read(&safe_path)
                 .map_err(|e| YetiError::Internal(format!("Cannot read file: {}", e)))?;
+            let meta = safe_path.metadata()
+                .map_err(|e| YetiError::Internal(format!("Cannot read file: {}", e)))?;
+            let etag = etag_for(&content, &meta);
 
-            // Check if content is valid UTF-8
-            match String::from_utf8(content) {
-                Ok(text) => {
-                    let size = safe_path.metadata().map(|m| m.len()).unwrap_or(0);
-                    return reply().json(json!({
-                        "app": app_id,
-                        "path": rel_path,
-                        "type": "file",
-                        "content": text,
-                        "size": size,
-                    }));
-                }
-                Err(_) => {
-                    return bad_request("File is not valid UTF-8 text");
-                }
+            // Raw bytes: inline for previews, attachment for downloads
+            let raw = parse_query_param(query, "raw").as_deref() == Some("true");
+            let download = parse_query_param(query, "download").as_deref() == Some("true");
+            if raw || download {
+                let file_name = safe_path.file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                // App files are untrusted: never sniffed, and HTML/SVG opened from
+                // the admin origin renders sandboxed (no scripts, opaque origin)
+                return reply()
+                    .header("ETag", &etag)
+                    .header("Content-Type", mime_type(&safe_path))
+                    .header("Content-Disposition", &content_disposition(if download { "attachment" } else { "inline" }, &file_name))
+                    .header("X-Content-Type-Options", "nosniff")
+                    .header("Content-Security-Policy", "sandbox")
+                    .body(content);
             }
+
+            // Text comes back as-is; binary (or `encoding=base64`) as base64
+            let size = content.len();
+            let force_base64 = parse_query_param(query, "encoding").as_deref() == Some("base64");
+            let (content, encoding) = match String::from_utf8(content) {
+                Ok(text) if !force_base64 => (text, "utf-8"),
+                Ok(text) => (BASE64.encode(text), "base64"),
+                Err(e) => (BASE64.encode(e.into_bytes()), "base64"),
+            };
+            return reply().header("ETag", &etag).json(json!({
+                "app": app_id,
+                "path": rel_path,
+                "type": "file",
+                "content": content,
+                "encoding": encoding,
+                "mime_type": mime_type(&safe_path),
+                "size": size,
+                "etag": etag,
+            }));
         }
 
         not_found(&format!("Path '{}' not found in app '{}'", rel_path, app_id))
     });
 
-    post!(request, _ctx, {
-        let body = request.json_value()?;
-        let app_id = body.require_str("app")?;
-        let rel_path = body.require_str("path")?;
-        let content = body.require_str("content")?;
+    super::audit::audited!("files", post, request, ctx, {
+        // Restore a backup over the current file (which is backed up first)
+        if request.uri().path().trim_end_matches('/').ends_with("/backups/restore") {
+            let body = request.json_value()?;
+            let app_id = body.require_str("app")?;
+            let rel_path = body.require_str("path")?;
+            let id = body.require_str("backup")?;
 
-        let safe_path = resolve_safe_path(&app_id, &rel_path)?;
+            let safe_path = resolve_safe_path(&app_id, &rel_path)?;
+            if is_secret_store_path(&safe_path) {
+                return not_found(&format!("Path '{}' not found in app '{}'", rel_path, app_id));
+            }
+            let key = app_relative_path(&app_id, &rel_path, &safe_path);
+            let Some(path) = backup_path(&app_id, &key, &id) else {
+                return not_found(&format!("Backup '{}' of '{}' not found", id, rel_path));
+            };
+            let content = std::fs::read(&path)
+                .map_err(|e| YetiError::Internal(format!("Cannot read backup: {}", e)))?;
+
+            if is_app_config(&key) {
+                if let Some(rejection) = super::apps::config_text_rejection(&content, &app_id) {
+                    return reply().code(422).json(rejection);
+                }
+            }
+
+            let if_match = request.headers()
+                .get("if-match")
+                .and_then(|v| v.to_str().ok())
+                .map(String::from);
+            let _guard = FILE_WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(rejection) = if_match_rejection(if_match.as_deref(), &app_id, &rel_path, &safe_path) {
+                return reply().code(412).json(rejection);
+            }
+            let previous = backup_file(&app_id, &key, &safe_path).map_err(YetiError::Internal)?;
+            if let Some(parent) = safe_path.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| YetiError::Internal(format!("Failed to create directories: {}", e)))?;
+            }
+            write_atomic(&safe_path, &content)
+                .map_err(|e| YetiError::Internal(format!("Failed to write file: {}", e)))?;
+
+            return reply().json(json!({
+                "app": app_id,
+                "path": rel_path,
+                "restored": id,
+                "backup": previous,
+                "size": content.len(),
+                "etag": file_version(&safe_path).map(|v| v["etag"].clone()),
+            }));
+        }
+
+        let content_type = request.headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+        let content_length = request.headers()
+            .get("content-length")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok());
+        let Upload { app_id, rel_path, content } = match parse_upload(content_type, content_length, request.uri().query().unwrap_or(""), request.body())? {
+            Ok(upload) => upload,
+            Err(rejection) => return reply().code(413).json(rejection),
+        };
 
-        if safe_path.exists() {
-            return bad_request(&format!("File '{}' already exists, use PUT to update", rel_path));
+        let safe_path = resolve_safe_path(&app_id, &rel_path)?;
+        if is_secret_store_path(&safe_path) {
+            return not_found(&format!("Path '{}' not found in app '{}'", rel_path, app_id));
         }
 
-        // Create parent directories if needed
-        if let Some(parent) = safe_path.parent() {
-            std::fs::create_dir_all(parent)
-                .map_err(|e| YetiError::Internal(format!("Failed to create directories: {}", e)))?;
+        // config.yaml must parse and match the config model
+        if is_app_config(&rel_path) {
+            if let Some(rejection) = super::apps::config_text_rejection(&content, &app_id) {
+                return reply().code(422).json(rejection);
+            }
         }
 
-        std::fs::write(&safe_path, &content)
-            .map_err(|e| YetiError::Internal(format!("Failed to write file: {}", e)))?;
+        // The existence check and the write happen under one lock
+        let etag = {
+            let _guard = FILE_WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            if safe_path.exists() {
+                return bad_request(&format!("File '{}' already exists, use PUT to update", rel_path));
+            }
+
+            // Create parent directories if needed
+            if let Some(parent) = safe_path.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| YetiError::Internal(format!("Failed to create directories: {}", e)))?;
+            }
+
+            write_atomic(&safe_path, &content)
+                .map_err(|e| YetiError::Internal(format!("Failed to write file: {}", e)))?;
+            file_version(&safe_path).map(|v| v["etag"].clone())
+        };
 
         reply().code(201).json(json!({
             "app": app_id,
             "path": rel_path,
             "created": true,
             "size": content.len(),
+            "etag": etag,
         }))
     });
 
-    put!(request, _ctx, {
-        let body = request.json_value()?;
-        let app_id = body.require_str("app")?;
-        let rel_path = body.require_str("path")?;
-        let content = body.require_str("content")?;
+    super::audit::audited!("files", put, request, ctx, {
+        let content_type = request.headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+        let if_match = request.headers()
+            .get("if-match")
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        let content_length = request.headers()
+            .get("content-length")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok());
+        let Upload { app_id, rel_path, content } = match parse_upload(content_type, content_length, request.uri().query().unwrap_or(""), request.body())? {
+            Ok(upload) => upload,
+            Err(rejection) => return reply().code(413).json(rejection),
+        };
 
         let safe_path = resolve_safe_path(&app_id, &rel_path)?;
+        if is_secret_store_path(&safe_path) {
+            return not_found(&format!("Path '{}' not found in app '{}'", rel_path, app_id));
+        }
 
         if !safe_path.exists() {
             return not_found(&format!("File '{}' not found in app '{}'", rel_path, app_id));
         }
 
-        std::fs::write(&safe_path, &content)
-            .map_err(|e| YetiError::Internal(format!("Failed to write file: {}", e)))?;
+        // config.yaml must parse and match the config model
+        if is_app_config(&rel_path) {
+            if let Some(rejection) = super::apps::config_text_rejection(&content, &app_id) {
+                return reply().code(422).json(rejection);
+            }
+        }
+
+        // The If-Match check and the write happen under one lock
+        let (etag, backup) = {
+            let _guard = FILE_WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(rejection) = if_match_rejection(if_match.as_deref(), &app_id, &rel_path, &safe_path) {
+                return reply().code(412).json(rejection);
+            }
+            let backup = backup_file(&app_id, &app_relative_path(&app_id, &rel_path, &safe_path), &safe_path)
+                .map_err(YetiError::Internal)?;
+            write_atomic(&safe_path, &content)
+                .map_err(|e| YetiError::Internal(format!("Failed to write file: {}", e)))?;
+            (file_version(&safe_path).map(|v| v["etag"].clone()), backup)
+        };
+
+        // Optional compile check after saving Rust sources
+        let mut check = None;
+        if safe_path.extension().is_some_and(|ext| ext == "rs") {
+            let requested = parse_query_param(request.uri().query().unwrap_or(""), "check")
+                .map(|v| v == "true")
+                .unwrap_or_else(super::apps::check_on_save);
+            if requested {
+                check = Some(super::apps::compile_check(&app_id).await
+                    .unwrap_or_else(|e| json!({"app_id": app_id, "error": e.to_string()})));
+            }
+        }
 
         reply().json(json!({
             "app": app_id,
             "path": rel_path,
             "updated": true,
             "size": content.len(),
+            "etag": etag,
+            "backup": backup,
+            "check": check,
         }))
     });
 
-    delete!(request, _ctx, {
+    super::audit::audited!("files", delete, request, ctx, {
         let query = request.uri().query().unwrap_or("");
         let app_id = parse_required_query_param(query, "app")?;
         let rel_path = parse_required_query_param(query, "path")?;
 
         let safe_path = resolve_safe_path(&app_id, &rel_path)?;
+        if is_secret_store_path(&safe_path) {
+            return not_found(&format!("Path '{}' not found in app '{}'", rel_path, app_id));
+        }
 
         if !safe_path.exists() {
             return not_found(&format!("Path '{}' not found in app '{}'", rel_path, app_id));
         }
 
+        if is_protected_path(&app_id, &safe_path) {
+            let confirm = parse_query_param(query, "confirm");
+            if let Some(rejection) = super::apps::protection_rejection(&app_id, "deleted", confirm.as_deref()) {
+                return reply().code(403).json(rejection);
+            }
+        }
+
+        let if_match = request.headers()
+            .get("if-match")
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        let _guard = FILE_WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(rejection) = if_match_rejection(if_match.as_deref(), &app_id, &rel_path, &safe_path) {
+            return reply().code(412).json(rejection);
+        }
+
         if safe_path.is_dir() {
             std::fs::remove_dir_all(&safe_path)
                 .map_err(|e| YetiError::Internal(format!("Failed to remove directory: {}", e)))?;
         } else {
+            // Deleted files stay restorable from their backups
+            backup_file(&app_id, &app_relative_path(&app_id, &rel_path, &safe_path), &safe_path)
+                .map_err(YetiError::Internal)?;
             std::fs::remove_file(&safe_path)
                 .map_err(|e| YetiError::Internal(format!("Failed to remove file: {}", e)))?;
         }
@@ -105,7 +105,7 @@ impl Resource for KeysResource {
         reply().json(json!(keys))
     });
 
-    post!(request, _ctx, {
+    super::audit::audited!("keys", post, request, ctx, {
         let body = request.json_value()?;
         let name = body.require_str("name")?;
 
@@ -154,7 +154,7 @@ impl Resource for KeysResource {
         }))
     });
 
-    delete!(_request, ctx, {
+    super::audit::audited!("keys", delete, request, ctx, {
         let key_name = ctx.require_id()?.to_string();
 
         validate_identifier(&key_name, "key name")?;
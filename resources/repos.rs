@@ -54,25 +54,31 @@ fn git_ssh_command(key_name: &str) -> std::result::Result<String, String> {
         return Err(format!("SSH key '{}' not found", key_name));
     }
     Ok(format!(
-        "ssh -i {} -o StrictHostKeyChecking=accept-new -o IdentitiesOnly=yes",
+        "ssh -i {} -o StrictHostKeyChecking=accept-new -o IdentitiesOnly=yes -o BatchMode=yes",
         key_path.to_string_lossy()
     ))
 }
 
+/// A non-interactive `git` command, optionally authenticating with a named SSH key.
+/// Shared with the bulk pull in `apps`.
+pub(crate) fn git_command(key: Option<&str>) -> std::result::Result<std::process::Command, String> {
+    let mut cmd = std::process::Command::new("git");
+    cmd.env("GIT_TERMINAL_PROMPT", "0");
+    if let Some(key_name) = key {
+        cmd.env("GIT_SSH_COMMAND", git_ssh_command(key_name)?);
+    }
+    Ok(cmd)
+}
+
 /// Run a git command, optionally with SSH key
 fn run_git(args: &[&str], cwd: Option<&std::path::Path>, key: Option<&str>) -> std::result::Result<String, String> {
-    let mut cmd = std::process::Command::new("git");
+    let mut cmd = git_command(key)?;
     cmd.args(args);
 
     if let Some(dir) = cwd {
         cmd.current_dir(dir);
     }
 
-    if let Some(key_name) = key {
-        let ssh_cmd = git_ssh_command(key_name)?;
-        cmd.env("GIT_SSH_COMMAND", &ssh_cmd);
-    }
-
     let output = cmd.output()
         .map_err(|e| format!("Failed to run git: {}", e))?;
 
@@ -146,7 +152,7 @@ impl Resource for ReposResource {
         }))
     });
 
-    post!(request, _ctx, {
+    super::audit::audited!("repos", post, request, ctx, {
         let body = request.json_value()?;
 
         // Parse the request URI to determine the operation
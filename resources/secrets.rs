@@ -0,0 +1,430 @@
+//! Application Secrets Resource
+//!
+//! Per-app secrets, encrypted at rest. Values are write-only: nothing in this
+//! API ever returns a decrypted value.
+//!
+//! | Method | Path                                  | Description                 |
+//! |--------|---------------------------------------|-----------------------------|
+//! | GET    | /admin/secrets                        | Apps with secrets (counts)  |
+//! | GET    | /admin/secrets/{app_id}               | Secret names for an app     |
+//! | PUT    | /admin/secrets/{app_id}/{name}        | Set a secret ({"value"})    |
+//! | DELETE | /admin/secrets/{app_id}/{name}        | Remove a secret             |
+//!
+//! Each app's secrets live in `{root}/.admin-secrets/{app_id}.json`, sealed
+//! with AES-256-GCM under the master key `{root}/.admin-secrets.key` (created
+//! on first use, mode 0600). The app id and secret name are bound into each
+//! ciphertext, so entries cannot be moved between apps or names.
+//!
+//! Stores follow their app: a rename re-seals the store under the new id, a
+//! deleted app's store moves to `.admin-secrets/.trash/{entry}.json` until the
+//! trash entry is restored (re-sealed if restored under another id) or purged.
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use yeti_core::prelude::*;
+
+pub type Secrets = SecretsResource;
+
+#[derive(Default)]
+pub struct SecretsResource;
+
+/// Master key file, relative to the root directory
+const MASTER_KEY_FILE: &str = ".admin-secrets.key";
+
+/// Directory holding one store file per app, relative to the root directory
+const STORE_DIR: &str = ".admin-secrets";
+
+/// Directory under STORE_DIR holding the stores of apps in the trash
+const TRASH_DIR: &str = ".trash";
+
+/// Longest accepted secret name
+const MAX_NAME_LEN: usize = 128;
+
+/// Largest accepted secret value (bytes)
+const MAX_VALUE_BYTES: usize = 64 * 1024;
+
+/// Serialises read-modify-write cycles on the store files
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Split the request path into the segments that follow `/secrets`
+/// e.g. /admin/secrets/my-app/DB_URL -> ["my-app", "DB_URL"]
+fn route_segments(uri_path: &str) -> Vec<&str> {
+    let segments: Vec<&str> = uri_path.split('/').filter(|s| !s.is_empty()).collect();
+    match segments.iter().position(|s| *s == "secrets") {
+        Some(pos) => segments[pos + 1..].to_vec(),
+        None => Vec::new(),
+    }
+}
+
+/// Secret names follow environment variable rules: letters, digits and `_`, not starting with a digit
+fn validate_secret_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(YetiError::Validation(format!(
+            "Invalid secret name '{}': use letters, digits and '_' (max {} chars, not starting with a digit)",
+            name, MAX_NAME_LEN
+        )))
+    }
+}
+
+/// Restrict a file or directory to the owner
+fn restrict_permissions(path: &Path, mode: u32) -> std::result::Result<(), String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+            .map_err(|e| format!("Failed to set permissions on {}: {}", path.display(), e))?;
+    }
+    #[cfg(not(unix))]
+    let _ = (path, mode);
+    Ok(())
+}
+
+/// Ensure the store directory exists with 0700 permissions
+fn ensure_get_store_directory() -> std::result::Result<PathBuf, String> {
+    let dir = get_root_directory().join(STORE_DIR);
+    if !dir.exists() {
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create secrets directory: {}", e))?;
+        restrict_permissions(&dir, 0o700)?;
+    }
+    Ok(dir)
+}
+
+/// Load the master key, generating it (mode 0600) on first use
+fn load_master_key() -> std::result::Result<Key<Aes256Gcm>, String> {
+    let path = get_root_directory().join(MASTER_KEY_FILE);
+    if !path.exists() {
+        let key = Aes256Gcm::generate_key(OsRng);
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        match options.open(&path) {
+            Ok(mut file) => {
+                use std::io::Write;
+                file.write_all(BASE64.encode(key).as_bytes())
+                    .and_then(|_| file.sync_all())
+                    .map_err(|e| format!("Failed to write master key: {}", e))?;
+                restrict_permissions(&path, 0o600)?;
+                return Ok(key);
+            }
+            // Another request created it first; fall through and read it
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(format!("Failed to create master key: {}", e)),
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path)
+            .map_err(|e| format!("Cannot stat master key: {}", e))?
+            .permissions()
+            .mode();
+        if mode & 0o077 != 0 {
+            yeti_log!(warn, "Master key {} had mode {:o}, restricting to 0600", path.display(), mode & 0o777);
+            restrict_permissions(&path, 0o600)?;
+        }
+    }
+
+    let encoded = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Cannot read master key: {}", e))?;
+    let bytes = BASE64.decode(encoded.trim())
+        .map_err(|_| "Master key is not valid base64".to_string())?;
+    if bytes.len() != 32 {
+        return Err(format!("Master key must be 32 bytes, found {}", bytes.len()));
+    }
+    Ok(*Key::<Aes256Gcm>::from_slice(&bytes))
+}
+
+/// Associated data binding a ciphertext to its app and name
+fn secret_aad(app_id: &str, name: &str) -> Vec<u8> {
+    format!("{}/{}", app_id, name).into_bytes()
+}
+
+/// Encrypt a value into a store entry
+fn seal_secret(key: &Key<Aes256Gcm>, app_id: &str, name: &str, value: &str) -> std::result::Result<serde_json::Value, String> {
+    let cipher = Aes256Gcm::new(key);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let aad = secret_aad(app_id, name);
+    let ciphertext = cipher.encrypt(&nonce, Payload { msg: value.as_bytes(), aad: &aad })
+        .map_err(|_| "Failed to encrypt secret".to_string())?;
+    Ok(json!({
+        "nonce": BASE64.encode(nonce),
+        "ciphertext": BASE64.encode(ciphertext),
+    }))
+}
+
+/// Decrypt a store entry; used to confirm the master key still opens an app's store
+fn open_secret(key: &Key<Aes256Gcm>, app_id: &str, name: &str, entry: &serde_json::Value) -> std::result::Result<Vec<u8>, String> {
+    let decode = |field: &str| entry[field].as_str()
+        .and_then(|s| BASE64.decode(s).ok())
+        .ok_or_else(|| format!("Secret '{}' has a malformed {}", name, field));
+    let nonce = decode("nonce")?;
+    let ciphertext = decode("ciphertext")?;
+    if nonce.len() != 12 {
+        return Err(format!("Secret '{}' has a malformed nonce", name));
+    }
+    let aad = secret_aad(app_id, name);
+    Aes256Gcm::new(key)
+        .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: &aad })
+        .map_err(|_| format!("Secret '{}' cannot be decrypted with the current master key", name))
+}
+
+/// Path of an app's store file
+fn store_path(app_id: &str) -> PathBuf {
+    get_root_directory().join(STORE_DIR).join(format!("{}.json", app_id))
+}
+
+/// Path of a deleted app's store file, kept until its trash entry is restored or purged
+fn trash_store_path(entry: &str) -> PathBuf {
+    get_root_directory().join(STORE_DIR).join(TRASH_DIR).join(format!("{}.json", entry))
+}
+
+/// Read an app's store: `{"secrets": {name: {nonce, ciphertext, updated_at, updated_by}}}`
+fn read_store(app_id: &str) -> std::result::Result<serde_json::Map<String, serde_json::Value>, String> {
+    read_store_at(&store_path(app_id), app_id)
+}
+
+/// `read_store` for a store file outside the usual path (e.g. in the trash)
+fn read_store_at(path: &Path, app_id: &str) -> std::result::Result<serde_json::Map<String, serde_json::Value>, String> {
+    if !path.exists() {
+        return Ok(serde_json::Map::new());
+    }
+    let content = std::fs::read(path)
+        .map_err(|e| format!("Cannot read secrets for '{}': {}", app_id, e))?;
+    let store: serde_json::Value = serde_json::from_slice(&content)
+        .map_err(|e| format!("Secrets store for '{}' is corrupt: {}", app_id, e))?;
+    Ok(store["secrets"].as_object().cloned().unwrap_or_default())
+}
+
+/// Replace an app's store (0600, written to a temp file and renamed into place)
+fn write_store(app_id: &str, secrets: serde_json::Map<String, serde_json::Value>) -> std::result::Result<(), String> {
+    write_store_at(&store_path(app_id), secrets)
+}
+
+/// `write_store` for a store file outside the usual path (e.g. in the trash)
+fn write_store_at(path: &Path, secrets: serde_json::Map<String, serde_json::Value>) -> std::result::Result<(), String> {
+    ensure_get_store_directory()?;
+    if secrets.is_empty() {
+        return match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to remove secrets store: {}", e)),
+            _ => Ok(()),
+        };
+    }
+
+    let dir = path.parent().unwrap_or(path);
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create secrets directory: {}", e))?;
+    let content = serde_json::to_vec_pretty(&json!({"version": 1, "secrets": secrets}))
+        .map_err(|e| format!("Failed to serialize secrets: {}", e))?;
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let tmp = dir.join(format!(".{}.tmp", file_name));
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&tmp)
+        .map_err(|e| format!("Failed to write secrets store: {}", e))?;
+    {
+        use std::io::Write;
+        file.write_all(&content)
+            .and_then(|_| file.sync_all())
+            .map_err(|e| format!("Failed to write secrets store: {}", e))?;
+    }
+    restrict_permissions(&tmp, 0o600)?;
+    std::fs::rename(&tmp, path)
+        .map_err(|e| format!("Failed to replace secrets store: {}", e))
+}
+
+// ── App lifecycle ──
+//
+// Called by the apps resource so a new app that reuses an id never inherits secrets.
+
+/// Move the store at `from` (sealed for `from_id`) to `to`, re-sealing it for `to_id`.
+/// A store already at `to` belongs to no live app and is replaced, or removed
+/// when there is nothing to move.
+fn move_store(from: &Path, from_id: &str, to: &Path, to_id: &str) -> std::result::Result<(), String> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut secrets = read_store_at(from, from_id)?;
+    if from_id != to_id && !secrets.is_empty() {
+        let key = load_master_key()?;
+        for (name, entry) in secrets.iter_mut() {
+            let value = open_secret(&key, from_id, name, entry)?;
+            let value = String::from_utf8(value)
+                .map_err(|_| format!("Secret '{}' is not valid UTF-8", name))?;
+            let mut resealed = seal_secret(&key, to_id, name, &value)?;
+            resealed["updated_at"] = entry["updated_at"].clone();
+            resealed["updated_by"] = entry["updated_by"].clone();
+            *entry = resealed;
+        }
+    }
+    write_store_at(to, secrets)?;
+    match std::fs::remove_file(from) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to remove secrets store: {}", e)),
+        _ => Ok(()),
+    }
+}
+
+/// Move a renamed app's secrets to its new id
+pub(crate) fn rename_app_secrets(old_id: &str, new_id: &str) -> std::result::Result<(), String> {
+    move_store(&store_path(old_id), old_id, &store_path(new_id), new_id)
+}
+
+/// Set a deleted app's secrets aside with its trash entry
+pub(crate) fn trash_app_secrets(app_id: &str, entry: &str) -> std::result::Result<(), String> {
+    move_store(&store_path(app_id), app_id, &trash_store_path(entry), app_id)
+}
+
+/// Give a restored trash entry its secrets back, under the id it was restored as
+pub(crate) fn restore_app_secrets(entry: &str, original_id: &str, app_id: &str) -> std::result::Result<(), String> {
+    move_store(&trash_store_path(entry), original_id, &store_path(app_id), app_id)
+}
+
+/// Drop the secrets of a purged trash entry
+pub(crate) fn purge_app_secrets(entry: &str) -> std::result::Result<(), String> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    match std::fs::remove_file(trash_store_path(entry)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to remove secrets store: {}", e)),
+        _ => Ok(()),
+    }
+}
+
+/// Names and metadata of an app's secrets, sorted by name (never values)
+fn secret_names(secrets: &serde_json::Map<String, serde_json::Value>) -> Vec<serde_json::Value> {
+    let mut names: Vec<serde_json::Value> = secrets.iter()
+        .map(|(name, entry)| json!({
+            "name": name,
+            "updated_at": entry["updated_at"],
+            "updated_by": entry["updated_by"],
+        }))
+        .collect();
+    names.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+    names
+}
+
+impl Resource for SecretsResource {
+    fn name(&self) -> &str {
+        "secrets"
+    }
+
+    get!(request, _ctx, {
+        let segments = route_segments(request.uri().path());
+        match segments.as_slice() {
+            [] => {
+                let dir = get_root_directory().join(STORE_DIR);
+                let mut apps = Vec::new();
+                if dir.is_dir() {
+                    let entries = std::fs::read_dir(&dir)
+                        .map_err(|e| YetiError::Internal(format!("Cannot read secrets dir: {}", e)))?;
+                    for entry in entries.flatten() {
+                        let file_name = entry.file_name().to_string_lossy().to_string();
+                        let Some(app_id) = file_name.strip_suffix(".json") else { continue };
+                        if file_name.starts_with('.') {
+                            continue;
+                        }
+                        let count = read_store(app_id).map(|s| s.len()).unwrap_or(0);
+                        apps.push(json!({"app_id": app_id, "count": count}));
+                    }
+                }
+                apps.sort_by(|a, b| a["app_id"].as_str().cmp(&b["app_id"].as_str()));
+                reply().json(json!(apps))
+            }
+            [app_id] => {
+                validate_identifier(app_id, "app_id")?;
+                let secrets = read_store(app_id).map_err(YetiError::Internal)?;
+                reply().json(json!({
+                    "app_id": app_id,
+                    "secrets": secret_names(&secrets),
+                }))
+            }
+            _ => not_found("Unknown secrets route"),
+        }
+    });
+
+    super::audit::audited!("secrets", put, request, ctx, {
+        let segments = route_segments(request.uri().path());
+        let [app_id, name] = segments.as_slice() else {
+            return bad_request("Use PUT /secrets/{app_id}/{name} with {\"value\": ...}");
+        };
+        validate_identifier(app_id, "app_id")?;
+        validate_secret_name(name)?;
+        if !get_apps_directory().join(app_id).is_dir() {
+            return not_found(&format!("Application '{}' not found", app_id));
+        }
+
+        let body = request.json_value()?;
+        let value = body.require_str("value")?;
+        if value.len() > MAX_VALUE_BYTES {
+            return bad_request(&format!("Secret value exceeds {} bytes", MAX_VALUE_BYTES));
+        }
+
+        let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let key = load_master_key().map_err(YetiError::Internal)?;
+        let mut secrets = read_store(app_id).map_err(YetiError::Internal)?;
+
+        // Refuse to mix entries sealed under different master keys
+        if let Some((existing, entry)) = secrets.iter().next() {
+            if let Err(e) = open_secret(&key, app_id, existing, entry) {
+                return reply().code(409).json(json!({
+                    "error": e,
+                    "hint": "The master key changed since this store was written; delete the stale secrets and set them again",
+                }));
+            }
+        }
+
+        let mut entry = seal_secret(&key, app_id, name, &value).map_err(YetiError::Internal)?;
+        let updated_at = chrono::Utc::now().to_rfc3339();
+        entry["updated_at"] = json!(updated_at);
+        entry["updated_by"] = json!(ctx.username().unwrap_or("unknown"));
+        let created = secrets.insert(name.to_string(), entry).is_none();
+        write_store(app_id, secrets).map_err(YetiError::Internal)?;
+
+        reply().code(if created { 201 } else { 200 }).json(json!({
+            "app_id": app_id,
+            "name": name,
+            "created": created,
+            "updated_at": updated_at,
+        }))
+    });
+
+    super::audit::audited!("secrets", delete, request, ctx, {
+        let segments = route_segments(request.uri().path());
+        let [app_id, name] = segments.as_slice() else {
+            return bad_request("Use DELETE /secrets/{app_id}/{name}");
+        };
+        validate_identifier(app_id, "app_id")?;
+        validate_secret_name(name)?;
+
+        let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut secrets = read_store(app_id).map_err(YetiError::Internal)?;
+        if secrets.remove(*name).is_none() {
+            return not_found(&format!("Secret '{}' not found for '{}'", name, app_id));
+        }
+        write_store(app_id, secrets).map_err(YetiError::Internal)?;
+
+        reply().json(json!({
+            "app_id": app_id,
+            "name": name,
+            "deleted": true,
+        }))
+    });
+}
+
+register_resource!(SecretsResource);